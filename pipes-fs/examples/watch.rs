@@ -0,0 +1,36 @@
+use std::time::Duration;
+
+use futures::{StreamExt, TryStreamExt};
+use pipes::Source;
+use pipes_fs::{FsSource, WatchEvent};
+use pipes_package::match_glob;
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("index.md"), "# Index").unwrap();
+
+    let watch = FsSource::new(dir.path().to_path_buf())
+        .pattern(match_glob("**/*.md"))
+        .watch()
+        .debounce(Duration::from_millis(50));
+
+    let root = dir.path().to_path_buf();
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        std::fs::write(root.join("post.md"), "# Post").unwrap();
+        std::fs::write(root.join("ignored.txt"), "").unwrap();
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        std::fs::remove_file(root.join("index.md")).unwrap();
+    });
+
+    let stream = Source::<()>::create_stream(watch, ());
+    let events = stream.take(3).try_collect::<Vec<_>>().await.unwrap();
+
+    for event in events {
+        match event {
+            WatchEvent::Changed(pkg) => println!("changed {}", pkg.path()),
+            WatchEvent::Removed(path) => println!("removed {path}"),
+        }
+    }
+}
@@ -0,0 +1,329 @@
+use std::{
+    fs::File,
+    io::Write,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use flate2::{Compression, write::GzEncoder};
+use futures::future::BoxFuture;
+use mime::Mime;
+use pipes::{Error, Report, Unit, Work};
+use pipes_package::{Bytes, Content, Package};
+use zip::{CompressionMethod, ZipWriter, write::SimpleFileOptions};
+
+use crate::{UnitFailed, body::temp_path};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    Zip,
+    Tar,
+    TarGz,
+}
+
+impl ArchiveFormat {
+    /// Guess the format from a file name ending in `.zip`, `.tar`, `.tar.gz` or `.tgz`
+    pub fn from_path(path: impl AsRef<Path>) -> Option<ArchiveFormat> {
+        let name = path.as_ref().file_name()?.to_str()?.to_ascii_lowercase();
+
+        if name.ends_with(".zip") {
+            Some(ArchiveFormat::Zip)
+        } else if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            Some(ArchiveFormat::TarGz)
+        } else if name.ends_with(".tar") {
+            Some(ArchiveFormat::Tar)
+        } else {
+            None
+        }
+    }
+}
+
+enum Writer {
+    Zip(ZipWriter<File>),
+    Tar(tar::Builder<File>),
+    TarGz(tar::Builder<GzEncoder<File>>),
+}
+
+impl Writer {
+    fn create(path: &Path, format: ArchiveFormat) -> Result<Writer, Error> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(Error::new)?;
+        }
+
+        let file = File::create(path).map_err(Error::new)?;
+
+        Ok(match format {
+            ArchiveFormat::Zip => Writer::Zip(ZipWriter::new(file)),
+            ArchiveFormat::Tar => Writer::Tar(tar::Builder::new(file)),
+            ArchiveFormat::TarGz => Writer::TarGz(tar::Builder::new(GzEncoder::new(
+                file,
+                Compression::default(),
+            ))),
+        })
+    }
+
+    fn append(&mut self, path: &str, mime: &Mime, content: &[u8]) -> Result<(), Error> {
+        match self {
+            Writer::Zip(zip) => {
+                let method = if is_compressed(mime) {
+                    CompressionMethod::Stored
+                } else {
+                    CompressionMethod::Deflated
+                };
+                let options = SimpleFileOptions::default()
+                    .compression_method(method)
+                    .unix_permissions(0o644);
+                zip.start_file(path, options).map_err(Error::new)?;
+                zip.write_all(content).map_err(Error::new)
+            }
+            Writer::Tar(tar) => append_tar(tar, path, content),
+            Writer::TarGz(tar) => append_tar(tar, path, content),
+        }
+    }
+
+    fn finish(self) -> Result<(), Error> {
+        match self {
+            Writer::Zip(zip) => zip
+                .finish()
+                .map_err(Error::new)?
+                .sync_all()
+                .map_err(Error::new),
+            Writer::Tar(tar) => tar
+                .into_inner()
+                .map_err(Error::new)?
+                .sync_all()
+                .map_err(Error::new),
+            Writer::TarGz(tar) => tar
+                .into_inner()
+                .map_err(Error::new)?
+                .finish()
+                .map_err(Error::new)?
+                .sync_all()
+                .map_err(Error::new),
+        }
+    }
+}
+
+fn append_tar<W: Write>(
+    tar: &mut tar::Builder<W>,
+    path: &str,
+    content: &[u8],
+) -> Result<(), Error> {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(content.len() as u64);
+    header.set_mode(0o644);
+    header.set_cksum();
+    tar.append_data(&mut header, path, content)
+        .map_err(Error::new)
+}
+
+/// Whether content of this type is already compressed, so compressing it again is wasted work
+fn is_compressed(mime: &Mime) -> bool {
+    match (mime.type_(), mime.subtype().as_str()) {
+        (mime::IMAGE, "svg" | "bmp" | "tiff" | "x-icon") => false,
+        (mime::IMAGE | mime::AUDIO | mime::VIDEO, _) => true,
+        (mime::FONT, "woff" | "woff2") => true,
+        (mime::APPLICATION, sub) => matches!(
+            sub,
+            "zip" | "gzip" | "x-gzip" | "zstd" | "x-bzip2" | "x-xz" | "x-7z-compressed" | "pdf"
+        ),
+        _ => false,
+    }
+}
+
+struct State {
+    path: PathBuf,
+    // Entries are written here and only renamed to `path` once finished
+    tmp: PathBuf,
+    format: ArchiveFormat,
+    writer: Option<Writer>,
+    finished: bool,
+}
+
+impl State {
+    fn discard(&mut self) -> Result<(), Error> {
+        self.finished = true;
+        // Dropping the writer may still write to the file, so drop it before removing
+        self.writer.take();
+        match std::fs::remove_file(&self.tmp) {
+            Err(err) if err.kind() != std::io::ErrorKind::NotFound => Err(Error::new(err)),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl Drop for State {
+    fn drop(&mut self) {
+        if !self.finished {
+            let _ = self.discard();
+        }
+    }
+}
+
+/// Appends every package to an archive at its package path. Entries go to a temporary
+/// file next to the archive, which is only moved into place by [`ArchiveDest::finish`],
+/// eg. through [`ArchiveDest::finish_after`]. An archive that is never finished is removed.
+#[derive(Clone)]
+pub struct ArchiveDest {
+    state: Arc<Mutex<State>>,
+}
+
+impl ArchiveDest {
+    pub fn new(path: impl Into<PathBuf>, format: ArchiveFormat) -> ArchiveDest {
+        let path = path.into();
+        ArchiveDest {
+            state: Arc::new(Mutex::new(State {
+                tmp: temp_path(&path),
+                path,
+                format,
+                writer: None,
+                finished: false,
+            })),
+        }
+    }
+
+    /// Zip archive. Already compressed content, like most images, is stored as is.
+    pub fn zip(path: impl Into<PathBuf>) -> ArchiveDest {
+        ArchiveDest::new(path, ArchiveFormat::Zip)
+    }
+
+    pub fn tar(path: impl Into<PathBuf>) -> ArchiveDest {
+        ArchiveDest::new(path, ArchiveFormat::Tar)
+    }
+
+    pub fn tar_gz(path: impl Into<PathBuf>) -> ArchiveDest {
+        ArchiveDest::new(path, ArchiveFormat::TarGz)
+    }
+
+    /// Write the end of the archive and move it into place. An archive without entries is still created.
+    pub async fn finish(&self) -> Result<(), Error> {
+        let state = self.state.clone();
+        tokio::task::spawn_blocking(move || {
+            let mut state = state.lock().expect("lock");
+            if state.finished {
+                return Ok(());
+            }
+
+            let writer = match state.writer.take() {
+                Some(writer) => writer,
+                None => Writer::create(&state.tmp, state.format)?,
+            };
+
+            if let Err(err) = writer.finish() {
+                state.discard()?;
+                return Err(err);
+            }
+
+            state.finished = true;
+            if let Err(err) = std::fs::rename(&state.tmp, &state.path) {
+                let _ = std::fs::remove_file(&state.tmp);
+                return Err(Error::new(err));
+            }
+
+            Ok(())
+        })
+        .await
+        .map_err(Error::new)?
+    }
+
+    /// Remove the unfinished archive. Nothing is written at the archive path.
+    pub async fn discard(&self) -> Result<(), Error> {
+        let state = self.state.clone();
+        tokio::task::spawn_blocking(move || {
+            let mut state = state.lock().expect("lock");
+            if state.finished {
+                return Ok(());
+            }
+            state.discard()
+        })
+        .await
+        .map_err(Error::new)?
+    }
+
+    /// Run `unit`, then finish the archive, or discard it if any item failed.
+    /// See [`UnitFailed`] for how the unit should write to this destination.
+    pub fn finish_after<U>(&self, unit: U) -> Finish<U> {
+        Finish {
+            unit,
+            dest: self.clone(),
+        }
+    }
+}
+
+impl<C, B> Work<C, Package<B>> for ArchiveDest
+where
+    B: Content + Send + 'static,
+{
+    type Output = Package<B>;
+
+    type Future<'a>
+        = BoxFuture<'a, Result<Self::Output, Error>>
+    where
+        Self: 'a;
+
+    fn call<'a>(&'a self, _ctx: C, mut package: Package<B>) -> Self::Future<'a> {
+        Box::pin(async move {
+            let content: Bytes = package.content_mut().bytes().await?;
+            let path = package.path().as_str().to_string();
+            let mime = package.mime().clone();
+            let state = self.state.clone();
+
+            tokio::task::spawn_blocking(move || {
+                let mut state = state.lock().expect("lock");
+                if state.finished {
+                    return Err(Error::new("archive already finished"));
+                }
+
+                if state.writer.is_none() {
+                    state.writer = Some(Writer::create(&state.tmp, state.format)?);
+                }
+
+                state
+                    .writer
+                    .as_mut()
+                    .expect("writer")
+                    .append(&path, &mime, &content)
+            })
+            .await
+            .map_err(Error::new)??;
+
+            Ok(package)
+        })
+    }
+}
+
+pub struct Finish<U> {
+    unit: U,
+    dest: ArchiveDest,
+}
+
+impl<U, C> Unit<C> for Finish<U>
+where
+    U: Unit<C, Output = Report> + 'static,
+    for<'a> U::Future<'a>: Send,
+    C: 'static,
+{
+    /// The report of the unit, or a [`UnitFailed`] error if any item failed
+    type Output = Result<Report, Error>;
+
+    type Future<'a>
+        = BoxFuture<'a, Self::Output>
+    where
+        Self: 'a;
+
+    fn run<'a>(self, ctx: C) -> Self::Future<'a> {
+        let future = self.unit.run(ctx);
+        let dest = self.dest;
+
+        Box::pin(async move {
+            let report = future.await;
+            if !report.is_success() {
+                dest.discard().await?;
+                return Err(Error::new(UnitFailed::new(report)));
+            }
+
+            dest.finish().await?;
+            Ok(report)
+        })
+    }
+}
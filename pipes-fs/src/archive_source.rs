@@ -0,0 +1,275 @@
+use std::{
+    fs::File,
+    io::{Cursor, Read, Seek},
+    path::{Component, Path, PathBuf},
+};
+
+use flate2::read::GzDecoder;
+use futures::{StreamExt, future::BoxFuture, stream::BoxStream};
+use pipes::{Error, Source, Work};
+use pipes_package::{Bytes, Content, Package};
+use relative_path::{RelativePath, RelativePathBuf};
+use zip::ZipArchive;
+
+use crate::{Body, archive::ArchiveFormat};
+
+/// Default for [`ArchiveSource::max_entry_size`] and [`ExpandArchive::max_entry_size`]
+pub const DEFAULT_MAX_ENTRY_SIZE: u64 = 1 << 30;
+
+// Sizes in archive headers are not trusted, so at most this much is allocated up front
+const PREALLOCATE_LIMIT: u64 = 1 << 20;
+
+/// Yields every file in an archive as a package, with the path it has inside the archive
+pub struct ArchiveSource {
+    path: PathBuf,
+    format: Option<ArchiveFormat>,
+    max_entry_size: u64,
+}
+
+impl ArchiveSource {
+    /// The format is guessed from the file name, see [`ArchiveFormat::from_path`]
+    pub fn new(path: impl Into<PathBuf>) -> ArchiveSource {
+        ArchiveSource {
+            path: path.into(),
+            format: None,
+            max_entry_size: DEFAULT_MAX_ENTRY_SIZE,
+        }
+    }
+
+    pub fn format(mut self, format: ArchiveFormat) -> Self {
+        self.format = Some(format);
+        self
+    }
+
+    /// Fail on entries larger than `size` bytes once extracted. Defaults to 1 GiB.
+    pub fn max_entry_size(mut self, size: u64) -> Self {
+        self.max_entry_size = size;
+        self
+    }
+}
+
+impl<C> Source<C> for ArchiveSource {
+    type Item = Package<Body>;
+
+    type Stream<'a>
+        = BoxStream<'a, Result<Self::Item, Error>>
+    where
+        Self: 'a;
+
+    fn create_stream<'a>(self, _ctx: C) -> Self::Stream<'a> {
+        let format = self.format.or_else(|| ArchiveFormat::from_path(&self.path));
+        let path = self.path;
+        let limit = self.max_entry_size;
+
+        entries(move |emit| {
+            let format = format.ok_or_else(|| unknown_format(&path))?;
+            let file = File::open(&path).map_err(Error::new)?;
+            read_entries(file, format, limit, emit)
+        })
+    }
+}
+
+/// Expands an archive package into a stream of its files. Use with
+/// [`SourceExt::flatten`](pipes::SourceExt::flatten) to continue with the entries.
+#[derive(Debug, Clone, Copy)]
+pub struct ExpandArchive {
+    format: Option<ArchiveFormat>,
+    max_entry_size: u64,
+}
+
+impl Default for ExpandArchive {
+    fn default() -> Self {
+        ExpandArchive {
+            format: None,
+            max_entry_size: DEFAULT_MAX_ENTRY_SIZE,
+        }
+    }
+}
+
+impl ExpandArchive {
+    /// The format is guessed from the package path, see [`ArchiveFormat::from_path`]
+    pub fn new() -> ExpandArchive {
+        ExpandArchive::default()
+    }
+
+    pub fn format(mut self, format: ArchiveFormat) -> Self {
+        self.format = Some(format);
+        self
+    }
+
+    /// Fail on entries larger than `size` bytes once extracted. Defaults to 1 GiB.
+    pub fn max_entry_size(mut self, size: u64) -> Self {
+        self.max_entry_size = size;
+        self
+    }
+}
+
+impl<C, B> Work<C, Package<B>> for ExpandArchive
+where
+    B: Content + Send + 'static,
+{
+    type Output = BoxStream<'static, Result<Package<Body>, Error>>;
+
+    type Future<'a>
+        = BoxFuture<'a, Result<Self::Output, Error>>
+    where
+        Self: 'a;
+
+    fn call<'a>(&'a self, _ctx: C, mut package: Package<B>) -> Self::Future<'a> {
+        Box::pin(async move {
+            let path = package.path().to_path("");
+            let format = self
+                .format
+                .or_else(|| ArchiveFormat::from_path(&path))
+                .ok_or_else(|| unknown_format(&path))?;
+
+            let bytes: Bytes = package.content_mut().bytes().await?;
+            let limit = self.max_entry_size;
+
+            Ok(entries(move |emit| {
+                read_entries(Cursor::new(bytes), format, limit, emit)
+            }))
+        })
+    }
+}
+
+fn unknown_format(path: &Path) -> Error {
+    Error::new(format!("unknown archive format: {}", path.display()))
+}
+
+/// Run `read` on a blocking thread, streaming the packages it emits
+fn entries<F>(read: F) -> BoxStream<'static, Result<Package<Body>, Error>>
+where
+    F: FnOnce(&mut dyn FnMut(Package<Body>) -> bool) -> Result<(), Error> + Send + 'static,
+{
+    let (sx, mut rx) = tokio::sync::mpsc::channel(8);
+
+    let handle = tokio::task::spawn_blocking(move || {
+        let result = read(&mut |package| sx.blocking_send(Ok(package)).is_ok());
+        if let Err(err) = result {
+            let _ = sx.blocking_send(Err(err));
+        }
+    });
+
+    async_stream::stream! {
+        while let Some(next) = rx.recv().await {
+            yield next;
+        }
+
+        if let Err(err) = handle.await {
+            yield Err(Error::new(err));
+        }
+    }
+    .boxed()
+}
+
+/// Read every file entry, stopping early when `emit` returns false
+fn read_entries<R: Read + Seek>(
+    reader: R,
+    format: ArchiveFormat,
+    limit: u64,
+    emit: &mut dyn FnMut(Package<Body>) -> bool,
+) -> Result<(), Error> {
+    match format {
+        ArchiveFormat::Zip => {
+            let mut zip = ZipArchive::new(reader).map_err(Error::new)?;
+            for idx in 0..zip.len() {
+                let mut file = zip.by_index(idx).map_err(Error::new)?;
+                if !file.is_file() {
+                    continue;
+                }
+
+                // Entries escaping the archive root are skipped
+                let Some(path) = file.enclosed_name().and_then(relative) else {
+                    continue;
+                };
+
+                let size = file.size();
+                let content = read_entry(&mut file, &path, size, limit)?;
+
+                if !emit(entry(path, content)) {
+                    break;
+                }
+            }
+            Ok(())
+        }
+        ArchiveFormat::Tar => read_tar(reader, limit, emit),
+        ArchiveFormat::TarGz => read_tar(GzDecoder::new(reader), limit, emit),
+    }
+}
+
+fn read_tar<R: Read>(
+    reader: R,
+    limit: u64,
+    emit: &mut dyn FnMut(Package<Body>) -> bool,
+) -> Result<(), Error> {
+    let mut archive = tar::Archive::new(reader);
+
+    for next in archive.entries().map_err(Error::new)? {
+        let mut next = next.map_err(Error::new)?;
+        if !next.header().entry_type().is_file() {
+            continue;
+        }
+
+        let Some(path) = relative(next.path().map_err(Error::new)?.into_owned()) else {
+            continue;
+        };
+
+        let size = next.size();
+        let content = read_entry(&mut next, &path, size, limit)?;
+
+        if !emit(entry(path, content)) {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+/// Read an entry of at most `limit` bytes. `size` is only a hint from the archive header.
+fn read_entry(
+    reader: &mut dyn Read,
+    path: &RelativePath,
+    size: u64,
+    limit: u64,
+) -> Result<Vec<u8>, Error> {
+    let too_large = || {
+        Error::new(format!(
+            "archive entry is larger than {limit} bytes: {path}"
+        ))
+    };
+
+    if size > limit {
+        return Err(too_large());
+    }
+
+    let mut content = Vec::with_capacity(size.min(PREALLOCATE_LIMIT) as usize);
+    reader
+        .take(limit.saturating_add(1))
+        .read_to_end(&mut content)
+        .map_err(Error::new)?;
+
+    if content.len() as u64 > limit {
+        return Err(too_large());
+    }
+
+    Ok(content)
+}
+
+fn relative(path: PathBuf) -> Option<RelativePathBuf> {
+    let normal = path
+        .components()
+        .all(|part| matches!(part, Component::Normal(_) | Component::CurDir));
+    if !normal {
+        return None;
+    }
+
+    RelativePathBuf::from_path(path)
+        .ok()
+        .map(|path| path.normalize())
+}
+
+fn entry(path: RelativePathBuf, content: Vec<u8>) -> Package<Body> {
+    let mime = mime_guess::from_path(path.as_str()).first_or_octet_stream();
+    Package::new(path, mime, Body::Bytes(content.into()))
+}
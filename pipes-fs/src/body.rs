@@ -2,7 +2,10 @@ use bytes::{BufMut, Bytes, BytesMut};
 use futures::{TryStreamExt, stream::BoxStream};
 use pipes::Error;
 use pipes_package::{Content, async_trait};
-use std::path::{Path, PathBuf};
+use std::{
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
+};
 use tokio::io::AsyncWriteExt;
 
 pub enum Body {
@@ -81,13 +84,77 @@ impl Body {
             Body::Path(path) => {
                 tokio::fs::copy(path, file_path).await.map_err(Error::new)?;
             }
-            Body::Empty => {}
+            Body::Empty => {
+                tokio::fs::File::create(file_path)
+                    .await
+                    .map_err(Error::new)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Write the body to `file_path` without holding it in memory, and point the body at
+    /// the written file. The file is written to a temporary path next to it and renamed,
+    /// so readers never see a partial file.
+    pub async fn persist(&mut self, file_path: &Path) -> Result<(), Error> {
+        let tmp = temp_path(file_path);
+
+        if let Err(err) = self.write_tmp(&tmp).await {
+            let _ = tokio::fs::remove_file(&tmp).await;
+            return Err(err);
+        }
+
+        if let Err(err) = tokio::fs::rename(&tmp, file_path).await {
+            let _ = tokio::fs::remove_file(&tmp).await;
+            return Err(Error::new(err));
+        }
+
+        *self = Body::Path(file_path.to_path_buf());
+
+        Ok(())
+    }
+
+    async fn write_tmp(&mut self, tmp: &Path) -> Result<(), Error> {
+        let mut file = if let Body::Path(path) = self {
+            tokio::fs::copy(path, tmp).await.map_err(Error::new)?;
+            tokio::fs::OpenOptions::new()
+                .write(true)
+                .open(tmp)
+                .await
+                .map_err(Error::new)?
+        } else {
+            tokio::fs::File::create(tmp).await.map_err(Error::new)?
+        };
+
+        match self {
+            Body::Bytes(bs) => file.write_all(bs).await.map_err(Error::new)?,
+            Body::Stream(stream) => {
+                while let Some(next) = stream.try_next().await? {
+                    file.write_all(&next).await.map_err(Error::new)?;
+                }
+            }
+            Body::Path(_) | Body::Empty => {}
         }
 
+        file.sync_all().await.map_err(Error::new)?;
+
         Ok(())
     }
 }
 
+/// A unique sibling of `path` for writing before renaming into place
+pub(crate) fn temp_path(path: &Path) -> PathBuf {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    path.with_file_name(format!(
+        ".{name}.{}.{}.tmp",
+        std::process::id(),
+        COUNTER.fetch_add(1, Ordering::Relaxed)
+    ))
+}
+
 #[async_trait]
 impl Content for Body {
     async fn bytes(&mut self) -> Result<Bytes, Error> {
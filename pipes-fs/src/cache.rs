@@ -0,0 +1,241 @@
+use std::{
+    any::TypeId,
+    collections::BTreeSet,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use futures::future::BoxFuture;
+use pipes::{Error, Work};
+use pipes_package::{Bytes, Content, Meta, Mime, Package};
+use relative_path::{RelativePath, RelativePathBuf};
+
+use crate::body::temp_path;
+
+// Bump when the entry layout changes so old entries are never read
+const VERSION: &str = "1";
+
+/// Persistent store of stage outputs, keyed by input path, input content and a
+/// fingerprint of the stage configuration
+#[derive(Debug, Clone)]
+pub struct Cache {
+    dir: Arc<PathBuf>,
+}
+
+impl Cache {
+    pub fn new(dir: impl Into<PathBuf>) -> Cache {
+        Cache {
+            dir: Arc::new(dir.into()),
+        }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Skip `work` for inputs it has already processed with the same `fingerprint`.
+    /// The fingerprint should describe everything about the stage that affects its output,
+    /// eg. `format!("save {format:?}")`, so changing the configuration invalidates old entries.
+    ///
+    /// Only the path, mime and content of the output are cached, so `work` must take and
+    /// produce content. Stages working on decoded values are cached together with the
+    /// stages around them, eg. `ImageWork::default().pipe(save(format))` instead of `save`.
+    ///
+    /// A hit keeps the meta of the input, so outputs whose meta differs from their input
+    /// are not cached. Without the `serde` feature only which meta a package carries is
+    /// compared, not the values, and input meta is not part of the key: a stage that reads
+    /// meta, or changes the value of meta it was given, must not be cached. With `serde`,
+    /// values of keys created with `MetaKey::serde` are part of the key and compared too.
+    pub fn cached<W>(&self, fingerprint: impl Into<String>, work: W) -> Cached<W> {
+        Cached {
+            work,
+            cache: self.clone(),
+            fingerprint: fingerprint.into(),
+        }
+    }
+
+    /// Remove all entries
+    pub async fn clear(&self) -> Result<(), Error> {
+        match tokio::fs::remove_dir_all(&*self.dir).await {
+            Err(err) if err.kind() != ErrorKind::NotFound => Err(Error::new(err)),
+            _ => Ok(()),
+        }
+    }
+
+    fn entry_path(&self, key: &str) -> PathBuf {
+        self.dir.join(&key[..2]).join(key)
+    }
+
+    async fn load(&self, key: &str) -> Result<Option<Entry>, Error> {
+        let bytes = match tokio::fs::read(self.entry_path(key)).await {
+            Ok(bytes) => Bytes::from(bytes),
+            Err(err) if err.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(Error::new(err)),
+        };
+
+        // An unreadable entry is treated like a missing one and overwritten
+        Ok(Entry::decode(bytes))
+    }
+
+    async fn store(&self, key: &str, entry: &Entry) -> Result<(), Error> {
+        let path = self.entry_path(key);
+        tokio::fs::create_dir_all(path.parent().expect("entry parent"))
+            .await
+            .map_err(Error::new)?;
+
+        // Write to a temporary file first so readers never see a partial entry
+        let tmp = temp_path(&path);
+
+        tokio::fs::write(&tmp, entry.encode())
+            .await
+            .map_err(Error::new)?;
+
+        if let Err(err) = tokio::fs::rename(&tmp, &path).await {
+            let _ = tokio::fs::remove_file(&tmp).await;
+            return Err(Error::new(err));
+        }
+
+        Ok(())
+    }
+}
+
+struct Entry {
+    path: RelativePathBuf,
+    mime: Mime,
+    content: Bytes,
+}
+
+impl Entry {
+    // Layout: path and mime on a line each, followed by the content
+    fn encode(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(self.content.len() + 64);
+        out.extend_from_slice(self.path.as_str().as_bytes());
+        out.push(b'\n');
+        out.extend_from_slice(self.mime.as_ref().as_bytes());
+        out.push(b'\n');
+        out.extend_from_slice(&self.content);
+        out
+    }
+
+    fn decode(bytes: Bytes) -> Option<Entry> {
+        let (path, rest) = split_line(&bytes)?;
+        let (mime, _) = split_line(rest)?;
+        let offset = path.len() + mime.len() + 2;
+
+        Some(Entry {
+            path: RelativePathBuf::from(path),
+            mime: mime.parse().ok()?,
+            content: bytes.slice(offset..),
+        })
+    }
+}
+
+fn split_line(bytes: &[u8]) -> Option<(&str, &[u8])> {
+    let idx = bytes.iter().position(|b| *b == b'\n')?;
+    let line = std::str::from_utf8(&bytes[..idx]).ok()?;
+    Some((line, &bytes[idx + 1..]))
+}
+
+#[derive(Debug, Clone)]
+pub struct Cached<W> {
+    work: W,
+    cache: Cache,
+    fingerprint: String,
+}
+
+impl<W> Cached<W> {
+    fn key(&self, path: &RelativePath, content: &[u8], meta: &[u8]) -> String {
+        let content = blake3::hash(content);
+
+        let mut hasher = blake3::Hasher::new();
+        for part in [
+            VERSION.as_bytes(),
+            path.as_str().as_bytes(),
+            content.as_bytes(),
+            meta,
+            self.fingerprint.as_bytes(),
+        ] {
+            hasher.update(&(part.len() as u64).to_le_bytes());
+            hasher.update(part);
+        }
+
+        hasher.finalize().to_hex().to_string()
+    }
+}
+
+impl<W, C, B, O> Work<C, Package<B>> for Cached<W>
+where
+    W: Work<C, Package<B>, Output = Package<O>> + Sync,
+    for<'a> W::Future<'a>: Send,
+    B: Content + Send + 'static,
+    O: Content + From<Bytes> + Send,
+    C: Send + 'static,
+{
+    type Output = Package<O>;
+
+    type Future<'a>
+        = BoxFuture<'a, Result<Self::Output, Error>>
+    where
+        Self: 'a;
+
+    fn call<'a>(&'a self, ctx: C, mut package: Package<B>) -> Self::Future<'a> {
+        Box::pin(async move {
+            let Some(meta) = MetaState::of(package.meta()) else {
+                return self.work.call(ctx, package).await;
+            };
+
+            let input = package.content_mut().bytes().await?;
+            let key = self.key(package.path(), &input, &meta.values);
+
+            if let Some(entry) = self.cache.load(&key).await? {
+                // Only entries of outputs with the meta of their input are stored
+                let mut output = package.map_content(O::from(entry.content));
+                output.set_path(entry.path);
+                output.set_mime(entry.mime);
+                return Ok(output);
+            }
+
+            let mut output = self.work.call(ctx, package).await?;
+
+            if MetaState::of(output.meta()).as_ref() != Some(&meta) {
+                return Ok(output);
+            }
+
+            let entry = Entry {
+                path: output.path().to_relative_path_buf(),
+                mime: output.mime().clone(),
+                content: output.content_mut().bytes().await?,
+            };
+
+            self.cache.store(&key, &entry).await?;
+
+            Ok(output)
+        })
+    }
+}
+
+/// Which meta a package carries, and the serialized values where possible,
+/// to tell whether a stage changed it
+#[derive(PartialEq, Eq)]
+struct MetaState {
+    types: BTreeSet<TypeId>,
+    names: BTreeSet<String>,
+    values: Vec<u8>,
+}
+
+impl MetaState {
+    /// `None` when the meta can not be serialized, so the package is not cached
+    fn of(meta: &Meta) -> Option<MetaState> {
+        #[cfg(feature = "serde")]
+        let values = serde_json::to_vec(meta).ok()?;
+        #[cfg(not(feature = "serde"))]
+        let values = Vec::new();
+
+        Some(MetaState {
+            types: meta.types().collect(),
+            names: meta.keys().map(String::from).collect(),
+            values,
+        })
+    }
+}
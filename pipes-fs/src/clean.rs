@@ -0,0 +1,167 @@
+use std::{
+    collections::HashSet,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+
+use futures::future::BoxFuture;
+use ignore::WalkBuilder;
+use pipes::{Error, Matcher, Report, Unit};
+use relative_path::RelativePathBuf;
+
+use crate::UnitFailed;
+
+/// Paths written by an `FsDest` during the current run
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Produced(Arc<Mutex<HashSet<PathBuf>>>);
+
+impl Produced {
+    pub(crate) fn insert(&self, path: PathBuf) {
+        self.0.lock().expect("lock").insert(path);
+    }
+
+    fn clear(&self) {
+        self.0.lock().expect("lock").clear();
+    }
+
+    fn contains(&self, path: &PathBuf) -> bool {
+        self.0.lock().expect("lock").contains(path)
+    }
+}
+
+pub struct Clean<U> {
+    unit: U,
+    root: PathBuf,
+    produced: Produced,
+    keep: Vec<Arc<dyn Matcher<RelativePathBuf>>>,
+    dry_run: bool,
+}
+
+impl<U> Clean<U> {
+    pub(crate) fn new(unit: U, root: PathBuf, produced: Produced, dry_run: bool) -> Clean<U> {
+        Clean {
+            unit,
+            root,
+            produced,
+            keep: Default::default(),
+            dry_run,
+        }
+    }
+
+    /// Never remove paths matching `pattern`. Matching directories are kept with their content.
+    pub fn keep<M: Matcher<RelativePathBuf> + 'static>(mut self, pattern: M) -> Self {
+        self.keep.push(Arc::new(pattern));
+        self
+    }
+}
+
+impl<U, C> Unit<C> for Clean<U>
+where
+    U: Unit<C, Output = Report> + 'static,
+    for<'a> U::Future<'a>: Send,
+    C: 'static,
+{
+    /// The report of the unit and the stale files that were removed, or a
+    /// [`UnitFailed`] error if any item failed
+    type Output = Result<(Report, Vec<PathBuf>), Error>;
+
+    type Future<'a>
+        = BoxFuture<'a, Self::Output>
+    where
+        Self: 'a;
+
+    fn run<'a>(self, ctx: C) -> Self::Future<'a> {
+        let Clean {
+            unit,
+            root,
+            produced,
+            keep,
+            dry_run,
+        } = self;
+
+        produced.clear();
+        let future = unit.run(ctx);
+
+        Box::pin(async move {
+            let report = future.await;
+            if !report.is_success() {
+                return Err(Error::new(UnitFailed::new(report)));
+            }
+
+            let removed =
+                tokio::task::spawn_blocking(move || remove_stale(root, &produced, &keep, dry_run))
+                    .await
+                    .map_err(Error::new)??;
+
+            Ok((report, removed))
+        })
+    }
+}
+
+fn remove_stale(
+    root: PathBuf,
+    produced: &Produced,
+    keep: &[Arc<dyn Matcher<RelativePathBuf>>],
+    dry_run: bool,
+) -> Result<Vec<PathBuf>, Error> {
+    if !root.exists() {
+        return Ok(Vec::new());
+    }
+
+    let relative = {
+        let root = root.clone();
+        move |path: &std::path::Path| {
+            pathdiff::diff_paths(path, &root).and_then(|path| RelativePathBuf::from_path(path).ok())
+        }
+    };
+
+    let keep = keep.to_vec();
+    let walk = WalkBuilder::new(&root)
+        .standard_filters(false)
+        .filter_entry(move |entry| {
+            if entry.depth() == 0 {
+                return true;
+            }
+            match relative(entry.path()) {
+                Some(path) => !keep.iter().any(|keep| keep.is_match(&path)),
+                None => true,
+            }
+        })
+        .build();
+
+    let mut removed = Vec::new();
+    let mut dirs = Vec::new();
+
+    for entry in walk {
+        let entry = entry.map_err(Error::new)?;
+        if entry.depth() == 0 {
+            continue;
+        }
+
+        if entry.file_type().is_some_and(|ty| ty.is_dir()) {
+            dirs.push(entry.into_path());
+            continue;
+        }
+
+        let path = entry.into_path();
+        if produced.contains(&path) {
+            continue;
+        }
+
+        if !dry_run {
+            std::fs::remove_file(&path).map_err(Error::new)?;
+        }
+
+        removed.push(path);
+    }
+
+    // Remove directories left empty, deepest first. Non empty ones fail and are kept.
+    if !dry_run {
+        dirs.sort_by_key(|dir| std::cmp::Reverse(dir.components().count()));
+        for dir in dirs {
+            let _ = std::fs::remove_dir(dir);
+        }
+    }
+
+    Ok(removed)
+}
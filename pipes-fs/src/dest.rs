@@ -1,4 +1,7 @@
-use std::path::PathBuf;
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+};
 
 use futures::future::BoxFuture;
 use mime::Mime;
@@ -6,19 +9,179 @@ use pipes::{Error, Work};
 use pipes_package::{IntoPackage, Package};
 use tokio::io::AsyncWriteExt;
 
-use crate::Body;
+use crate::{
+    Body,
+    clean::{Clean, Produced},
+};
+
+/// What [`FsDest`] does when the target file already exists
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OverwritePolicy {
+    #[default]
+    Overwrite,
+    SkipIfExists,
+    /// Skip when the existing file has the same content. The body is loaded into memory to compare.
+    SkipIfUnchanged,
+    /// Fail with a [`FileExists`] error
+    ErrorIfExists,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WriteAction {
+    Create,
+    Overwrite,
+    Skip,
+}
+
+/// Added to the meta of every package passing through [`FsDest`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WriteReport {
+    pub path: PathBuf,
+    pub action: WriteAction,
+    /// Nothing was written, the action is what would have happened
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileExists {
+    path: PathBuf,
+}
+
+impl FileExists {
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl fmt::Display for FileExists {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "file exists: {}", self.path.display())
+    }
+}
+
+impl std::error::Error for FileExists {}
 
 #[derive(Debug, Clone)]
 pub struct FsDest {
     path: std::path::PathBuf,
+    streaming: bool,
+    policy: OverwritePolicy,
+    dry_run: bool,
+    // Shared between clones so a `Clean` unit sees what the pipeline produced
+    produced: Produced,
 }
 
 impl FsDest {
     pub fn new(path: impl Into<PathBuf>) -> FsDest {
-        FsDest { path: path.into() }
+        FsDest {
+            path: path.into(),
+            streaming: false,
+            policy: OverwritePolicy::default(),
+            dry_run: false,
+            produced: Produced::default(),
+        }
+    }
+
+    /// Write bodies chunk by chunk through a temporary file, leaving each package
+    /// with a [`Body::Path`] to the written file instead of its content in memory
+    pub fn streaming(mut self) -> Self {
+        self.streaming = true;
+        self
+    }
+
+    pub fn policy(mut self, policy: OverwritePolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Write nothing, only attach a [`WriteReport`] of what would be written
+    pub fn dry_run(mut self) -> Self {
+        self.dry_run = true;
+        self
+    }
+
+    /// Run `unit`, then remove files in the output directory this destination did not
+    /// produce during the run. Nothing is removed if any item failed.
+    /// See [`UnitFailed`](crate::UnitFailed) for how the unit should write to this destination.
+    pub fn clean<U>(&self, unit: U) -> Clean<U> {
+        Clean::new(unit, self.path.clone(), self.produced.clone(), self.dry_run)
+    }
+
+    async fn action(&self, file_path: &Path, body: &mut Body) -> Result<WriteAction, Error> {
+        if self.claims() {
+            return self.claim(file_path).await;
+        }
+
+        if !tokio::fs::try_exists(file_path).await.map_err(Error::new)? {
+            return Ok(WriteAction::Create);
+        }
+
+        match self.policy {
+            OverwritePolicy::Overwrite => Ok(WriteAction::Overwrite),
+            OverwritePolicy::SkipIfExists => Ok(WriteAction::Skip),
+            OverwritePolicy::ErrorIfExists => Err(Error::new(FileExists {
+                path: file_path.to_path_buf(),
+            })),
+            OverwritePolicy::SkipIfUnchanged => {
+                let existing = tokio::fs::read(file_path).await.map_err(Error::new)?;
+                if body.bytes().await? == existing {
+                    Ok(WriteAction::Skip)
+                } else {
+                    Ok(WriteAction::Overwrite)
+                }
+            }
+        }
+    }
+
+    /// Whether the existence check must also create the file, so packages
+    /// with the same path running concurrently can't both see it missing
+    fn claims(&self) -> bool {
+        !self.dry_run
+            && matches!(
+                self.policy,
+                OverwritePolicy::SkipIfExists | OverwritePolicy::ErrorIfExists
+            )
+    }
+
+    async fn claim(&self, file_path: &Path) -> Result<WriteAction, Error> {
+        create_parent(file_path).await?;
+
+        let result = tokio::fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(file_path)
+            .await;
+
+        match result {
+            Ok(_) => Ok(WriteAction::Create),
+            Err(err) if err.kind() != std::io::ErrorKind::AlreadyExists => Err(Error::new(err)),
+            Err(_) if self.policy == OverwritePolicy::SkipIfExists => Ok(WriteAction::Skip),
+            Err(_) => Err(Error::new(FileExists {
+                path: file_path.to_path_buf(),
+            })),
+        }
+    }
+
+    async fn write(&self, file_path: &Path, body: &mut Body) -> Result<(), Error> {
+        create_parent(file_path).await?;
+
+        if self.streaming {
+            body.persist(file_path).await
+        } else {
+            body.write_to(file_path).await
+        }
     }
 }
 
+async fn create_parent(file_path: &Path) -> Result<(), Error> {
+    if let Some(parent) = file_path.parent() {
+        tokio::fs::create_dir_all(parent)
+            .await
+            .map_err(Error::new)?;
+    }
+    Ok(())
+}
+
 impl<C, T: IntoPackage<Body> + Send> Work<C, T> for FsDest
 where
     T::Future: Send,
@@ -29,16 +192,31 @@ where
     type Future<'a> = BoxFuture<'a, Result<Package<Body>, Error>>;
 
     fn call<'a>(&'a self, _ctx: C, req: T) -> Self::Future<'a> {
-        let path = self.path.clone();
         Box::pin(async move {
-            if !tokio::fs::try_exists(&path).await.map_err(Error::new)? {
-                tokio::fs::create_dir_all(&path).await.map_err(Error::new)?
-            }
             let mut package = req.into_package().await?;
 
-            let file_path = package.path().to_logical_path(&path);
+            let file_path = package.path().to_logical_path(&self.path);
+
+            let action = self.action(&file_path, package.content_mut()).await?;
+
+            self.produced.insert(file_path.clone());
+
+            if !self.dry_run && action != WriteAction::Skip {
+                let result = self.write(&file_path, package.content_mut()).await;
+                if let Err(err) = result {
+                    // Don't leave the claimed, empty file behind
+                    if self.claims() {
+                        let _ = tokio::fs::remove_file(&file_path).await;
+                    }
+                    return Err(err);
+                }
+            }
 
-            package.content_mut().write_to(&file_path).await?;
+            package.meta_mut().insert(WriteReport {
+                path: file_path,
+                action,
+                dry_run: self.dry_run,
+            });
 
             Ok(package)
         })
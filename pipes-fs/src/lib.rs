@@ -1,12 +1,38 @@
+#[cfg(feature = "archive")]
+mod archive;
+#[cfg(feature = "archive")]
+mod archive_source;
 mod dest;
+mod memory;
 // mod into_package;
 // mod package;
 mod body;
+mod cache;
+mod clean;
 mod resolver;
+mod unit;
 
 mod source;
+mod watch;
 mod work;
 
-pub use self::{body::Body, dest::*, source::FsSource, work::*};
+pub use self::{
+    body::Body,
+    cache::{Cache, Cached},
+    clean::Clean,
+    dest::*,
+    memory::{MemoryDest, MemoryFs, MemorySource},
+    resolver::{ResolveError, SymlinkPolicy},
+    source::FsSource,
+    unit::UnitFailed,
+    watch::{FsWatch, WatchEvent},
+    work::*,
+};
 
 pub use mime::{self, Mime};
+
+#[cfg(feature = "archive")]
+pub use self::{
+    archive::{ArchiveDest, ArchiveFormat, Finish},
+    archive_source::{ArchiveSource, DEFAULT_MAX_ENTRY_SIZE, ExpandArchive},
+};
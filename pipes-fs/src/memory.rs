@@ -0,0 +1,163 @@
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex},
+};
+
+use futures::{
+    StreamExt,
+    future::BoxFuture,
+    stream::{self, BoxStream},
+};
+use mime::Mime;
+use pipes::{Error, Source, Work};
+use pipes_package::{Bytes, Content, Package};
+use relative_path::{RelativePath, RelativePathBuf};
+
+#[derive(Debug, Default)]
+struct Inner {
+    files: BTreeMap<RelativePathBuf, (Mime, Bytes)>,
+    written: Vec<RelativePathBuf>,
+}
+
+/// In-memory file tree for hermetic tests. Use [`MemoryFs::source`] and [`MemoryFs::dest`]
+/// to read and write it from a pipeline. Clones share the same files.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryFs {
+    inner: Arc<Mutex<Inner>>,
+}
+
+impl MemoryFs {
+    pub fn new() -> MemoryFs {
+        MemoryFs::default()
+    }
+
+    /// Add a file with a mime guessed from its path
+    pub fn with_file(self, path: impl Into<RelativePathBuf>, content: impl Into<Bytes>) -> Self {
+        self.insert(path, content);
+        self
+    }
+
+    /// Add or replace a file with a mime guessed from its path
+    pub fn insert(&self, path: impl Into<RelativePathBuf>, content: impl Into<Bytes>) {
+        let path = path.into().normalize();
+        let mime = mime_guess::from_path(path.as_str()).first_or_octet_stream();
+        self.lock().files.insert(path, (mime, content.into()));
+    }
+
+    pub fn remove(&self, path: impl AsRef<RelativePath>) -> Option<Bytes> {
+        let path = path.as_ref().normalize();
+        self.lock().files.remove(&path).map(|(_, content)| content)
+    }
+
+    /// Content of the file at `path`
+    pub fn read(&self, path: impl AsRef<RelativePath>) -> Option<Bytes> {
+        let path = path.as_ref().normalize();
+        self.lock()
+            .files
+            .get(&path)
+            .map(|(_, content)| content.clone())
+    }
+
+    pub fn mime(&self, path: impl AsRef<RelativePath>) -> Option<Mime> {
+        let path = path.as_ref().normalize();
+        self.lock().files.get(&path).map(|(mime, _)| mime.clone())
+    }
+
+    pub fn contains(&self, path: impl AsRef<RelativePath>) -> bool {
+        let path = path.as_ref().normalize();
+        self.lock().files.contains_key(&path)
+    }
+
+    /// All paths, sorted
+    pub fn paths(&self) -> Vec<RelativePathBuf> {
+        self.lock().files.keys().cloned().collect()
+    }
+
+    /// Paths written through the destination, in the order they were written
+    pub fn written(&self) -> Vec<RelativePathBuf> {
+        self.lock().written.clone()
+    }
+
+    pub fn clear(&self) {
+        *self.lock() = Inner::default();
+    }
+
+    /// Source yielding the files present when its stream is created, sorted by path
+    pub fn source(&self) -> MemorySource {
+        MemorySource {
+            inner: self.inner.clone(),
+        }
+    }
+
+    /// Destination storing every package passing through it
+    pub fn dest(&self) -> MemoryDest {
+        MemoryDest {
+            inner: self.inner.clone(),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Inner> {
+        lock(&self.inner)
+    }
+}
+
+fn lock(inner: &Mutex<Inner>) -> std::sync::MutexGuard<'_, Inner> {
+    inner.lock().expect("lock")
+}
+
+#[derive(Debug, Clone)]
+pub struct MemorySource {
+    inner: Arc<Mutex<Inner>>,
+}
+
+impl<C> Source<C> for MemorySource {
+    type Item = Package<Bytes>;
+
+    type Stream<'a>
+        = BoxStream<'a, Result<Self::Item, Error>>
+    where
+        Self: 'a;
+
+    fn create_stream<'a>(self, _ctx: C) -> Self::Stream<'a> {
+        let files = lock(&self.inner)
+            .files
+            .iter()
+            .map(|(path, (mime, content))| Ok(Package::new(path, mime.clone(), content.clone())))
+            .collect::<Vec<_>>();
+
+        stream::iter(files).boxed()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MemoryDest {
+    inner: Arc<Mutex<Inner>>,
+}
+
+impl<C, B> Work<C, Package<B>> for MemoryDest
+where
+    B: Content + Send + 'static,
+{
+    type Output = Package<B>;
+
+    type Future<'a>
+        = BoxFuture<'a, Result<Self::Output, Error>>
+    where
+        Self: 'a;
+
+    fn call<'a>(&'a self, _ctx: C, mut package: Package<B>) -> Self::Future<'a> {
+        Box::pin(async move {
+            let content = package.content_mut().bytes().await?;
+            let path = package.path().normalize();
+
+            let mut inner = lock(&self.inner);
+            inner
+                .files
+                .insert(path.clone(), (package.mime().clone(), content));
+            inner.written.push(path);
+            drop(inner);
+
+            Ok(package)
+        })
+    }
+}
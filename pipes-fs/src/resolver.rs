@@ -1,14 +1,39 @@
-use std::path::{Path, PathBuf};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
-use async_walkdir::WalkDir;
-use futures::{Stream, TryStreamExt};
+use futures::Stream;
+use ignore::{DirEntry, Match, WalkBuilder, gitignore::Gitignore};
 
-pub use async_walkdir::Error as WalkDirError;
+pub use ignore::Error as ResolveError;
 use pipes::Matcher;
-use relative_path::RelativePathBuf;
+use relative_path::{RelativePath, RelativePathBuf};
 
+/// What to do with symbolic links met while walking
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SymlinkPolicy {
+    /// Yield the link itself without descending into it
+    #[default]
+    Keep,
+    /// Resolve links and descend into linked directories
+    Follow,
+    /// Leave links out entirely
+    Skip,
+}
+
+type BoxMatcher = Arc<dyn Matcher<RelativePathBuf>>;
+
+#[derive(Clone)]
 pub struct FileResolver {
-    patterns: Vec<Box<dyn Matcher<RelativePathBuf>>>,
+    patterns: Vec<BoxMatcher>,
+    excludes: Vec<BoxMatcher>,
+    ignore_files: Vec<String>,
+    git_ignore: bool,
+    hidden: bool,
+    max_depth: Option<usize>,
+    symlinks: SymlinkPolicy,
     root: PathBuf,
 }
 
@@ -16,6 +41,12 @@ impl FileResolver {
     pub fn new(path: PathBuf) -> FileResolver {
         FileResolver {
             patterns: Default::default(),
+            excludes: Default::default(),
+            ignore_files: Default::default(),
+            git_ignore: false,
+            hidden: true,
+            max_depth: None,
+            symlinks: SymlinkPolicy::default(),
             root: path,
         }
     }
@@ -23,7 +54,42 @@ impl FileResolver {
 
 impl FileResolver {
     pub fn pattern<M: Matcher<RelativePathBuf> + 'static>(mut self, pattern: M) -> Self {
-        self.patterns.push(Box::new(pattern));
+        self.patterns.push(Arc::new(pattern));
+        self
+    }
+
+    /// Leave out paths matching `pattern`. Matching directories are not descended into.
+    pub fn exclude<M: Matcher<RelativePathBuf> + 'static>(mut self, pattern: M) -> Self {
+        self.excludes.push(Arc::new(pattern));
+        self
+    }
+
+    /// Respect ignore files with the given name, eg. `.pipesignore`, using gitignore syntax
+    pub fn ignore_file(mut self, name: impl Into<String>) -> Self {
+        self.ignore_files.push(name.into());
+        self
+    }
+
+    /// Respect `.gitignore` files and skip `.git` directories
+    pub fn git_ignore(mut self, enable: bool) -> Self {
+        self.git_ignore = enable;
+        self
+    }
+
+    /// Whether to include hidden files and directories. Defaults to true.
+    pub fn hidden(mut self, include: bool) -> Self {
+        self.hidden = include;
+        self
+    }
+
+    /// Only descend `depth` directories below the root. A depth of 1 yields the root's entries.
+    pub fn max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = Some(depth);
+        self
+    }
+
+    pub fn symlinks(mut self, policy: SymlinkPolicy) -> Self {
+        self.symlinks = policy;
         self
     }
 
@@ -31,39 +97,248 @@ impl FileResolver {
         &self.root
     }
 
-    pub fn find(&self) -> impl Stream<Item = Result<RelativePathBuf, WalkDirError>> {
-        async_stream::try_stream! {
+    /// Whether a single path below the root passes the same rules as [`FileResolver::find`]
+    pub fn accepts(&self, path: &RelativePath, is_dir: bool) -> bool {
+        self.accepts_with(path, is_dir, &mut IgnoreCache::default())
+    }
+
+    /// Like [`FileResolver::accepts`], reusing ignore files already parsed into `cache`
+    pub(crate) fn accepts_with(
+        &self,
+        path: &RelativePath,
+        is_dir: bool,
+        cache: &mut IgnoreCache,
+    ) -> bool {
+        if path.as_str().is_empty() {
+            return false;
+        }
+
+        if self
+            .max_depth
+            .is_some_and(|depth| path.iter().count() > depth)
+        {
+            return false;
+        }
+
+        if !self.hidden && path.iter().any(|part| part.starts_with('.')) {
+            return false;
+        }
+
+        if self.git_ignore && path.iter().any(|part| part == ".git") {
+            return false;
+        }
+
+        // Excluded directories are pruned while walking, so their ancestors count too
+        let excluded = ancestors(path).any(|path| {
+            let path = path.to_relative_path_buf();
+            self.excludes.iter().any(|exclude| exclude.is_match(&path))
+        });
+        if excluded {
+            return false;
+        }
+
+        let full_path = path.to_logical_path(&self.root);
+
+        if self.symlinks == SymlinkPolicy::Skip && full_path.is_symlink() {
+            return false;
+        }
+
+        if self.is_ignored(path, &full_path, is_dir, cache) {
+            return false;
+        }
+
+        let path = path.to_relative_path_buf();
+        self.patterns.is_empty() || self.patterns.iter().any(|pattern| pattern.is_match(&path))
+    }
+
+    /// Names of the ignore files in effect, custom ones before `.gitignore`
+    pub(crate) fn ignore_names(&self) -> impl Iterator<Item = &str> {
+        self.ignore_files
+            .iter()
+            .map(String::as_str)
+            .chain(self.git_ignore.then_some(".gitignore"))
+    }
+
+    fn is_ignored(
+        &self,
+        path: &RelativePath,
+        full_path: &Path,
+        is_dir: bool,
+        cache: &mut IgnoreCache,
+    ) -> bool {
+        if self.ignore_names().next().is_none() {
+            return false;
+        }
+
+        // Deeper ignore files take precedence, as do custom ones over .gitignore
+        for dir in ancestors(path).skip(1).chain([RelativePath::new("")]) {
+            for ignore in cache.get(self, dir).iter() {
+                match ignore.matched_path_or_any_parents(full_path, is_dir) {
+                    Match::Ignore(_) => return true,
+                    Match::Whitelist(_) => return false,
+                    Match::None => {}
+                }
+            }
+        }
+
+        false
+    }
+
+    fn builder(&self, start: &Path) -> WalkBuilder {
+        let mut builder = WalkBuilder::new(start);
+
+        builder
+            .standard_filters(false)
+            .hidden(!self.hidden)
+            .git_ignore(self.git_ignore)
+            .git_exclude(self.git_ignore)
+            .require_git(false)
+            .max_depth(self.max_depth)
+            .follow_links(self.symlinks == SymlinkPolicy::Follow);
+
+        for name in &self.ignore_files {
+            builder.add_custom_ignore_filename(name);
+        }
+
+        let root = self.root.clone();
+        let excludes = self.excludes.clone();
+        let git_ignore = self.git_ignore;
+        let skip_links = self.symlinks == SymlinkPolicy::Skip;
+
+        // Rejecting an entry here also prunes everything below it
+        builder.filter_entry(move |entry| {
+            if entry.depth() == 0 {
+                return true;
+            }
+
+            if skip_links && entry.path_is_symlink() {
+                return false;
+            }
+
+            if git_ignore && is_dir(entry) && entry.file_name() == ".git" {
+                return false;
+            }
+
+            match relative(&root, entry.path()) {
+                Some(path) => !excludes.iter().any(|exclude| exclude.is_match(&path)),
+                None => true,
+            }
+        });
+
+        builder
+    }
+
+    pub fn find(&self) -> impl Stream<Item = Result<RelativePathBuf, ResolveError>> + use<> {
+        self.walk(&self.root, None)
+    }
+
+    /// Like [`FileResolver::find`], but only yields paths below `dir`
+    pub fn find_in(
+        &self,
+        dir: &RelativePath,
+    ) -> impl Stream<Item = Result<RelativePathBuf, ResolveError>> + use<> {
+        // Ignore files and the depth limit are relative to the root, which the walk does not see
+        self.walk(&dir.to_logical_path(&self.root), Some(self.clone()))
+    }
+
+    fn walk(
+        &self,
+        start: &Path,
+        resolver: Option<FileResolver>,
+    ) -> impl Stream<Item = Result<RelativePathBuf, ResolveError>> + use<> {
+        let walk = self.builder(start).build();
+        let root = self.root.clone();
+        let patterns = self.patterns.clone();
 
-            let mut stream = WalkDir::new(&self.root);
+        let (sx, mut rx) = tokio::sync::mpsc::channel(32);
 
-            'main_loop:
-            while let Some(next) = stream.try_next().await? {
-                // Get relative path
-                let path = match pathdiff::diff_paths(next.path(), &self.root) {
-                    Some(path) => path,
-                    None => {
-                        continue 'main_loop;
+        // The walker does blocking io
+        let handle = tokio::task::spawn_blocking(move || {
+            for next in walk {
+                // Errors, like a bad line in an ignore file, only concern one entry
+                let next = match next {
+                    Ok(next) => next,
+                    Err(err) => {
+                        if sx.blocking_send(Err(err)).is_err() {
+                            return;
+                        }
+                        continue;
                     }
                 };
 
-                let Ok(path) = RelativePathBuf::from_path(path) else {
-                    continue
+                if next.depth() == 0 {
+                    continue;
+                }
+
+                let Some(path) = relative(&root, next.path()) else {
+                    continue;
                 };
 
-                if self.patterns.is_empty() {
-                    yield path;
-                } else {
-                    for pattern in &self.patterns {
-                        if pattern.is_match(&path) {
-                            yield path;
-                            continue 'main_loop;
-                        }
-                    }
+                if !patterns.is_empty() && !patterns.iter().any(|pattern| pattern.is_match(&path)) {
+                    continue;
+                }
+
+                if let Some(resolver) = &resolver
+                    && !resolver.accepts(&path, is_dir(&next))
+                {
+                    continue;
                 }
 
+                if sx.blocking_send(Ok(path)).is_err() {
+                    // Receiver dropped
+                    return;
+                }
+            }
+        });
 
+        async_stream::stream! {
+            while let Some(next) = rx.recv().await {
+                yield next;
             }
 
+            if let Err(err) = handle.await {
+                yield Err(ResolveError::from(std::io::Error::other(err)));
+            }
         }
     }
 }
+
+/// Ignore files parsed by directory, so checking many paths reads each of them once
+#[derive(Default)]
+pub(crate) struct IgnoreCache {
+    dirs: HashMap<RelativePathBuf, Arc<[Gitignore]>>,
+}
+
+impl IgnoreCache {
+    fn get(&mut self, resolver: &FileResolver, dir: &RelativePath) -> Arc<[Gitignore]> {
+        self.dirs
+            .entry(dir.to_relative_path_buf())
+            .or_insert_with(|| {
+                let full_dir = dir.to_logical_path(&resolver.root);
+                resolver
+                    .ignore_names()
+                    .map(|name| Gitignore::new(full_dir.join(name)).0)
+                    .collect()
+            })
+            .clone()
+    }
+
+    /// Forget the ignore files of `dir`, eg. because one of them changed
+    pub(crate) fn invalidate(&mut self, dir: &RelativePath) {
+        self.dirs.remove(dir);
+    }
+}
+
+/// The path itself followed by its parents, without the empty root
+fn ancestors(path: &RelativePath) -> impl Iterator<Item = &RelativePath> {
+    std::iter::successors(Some(path), |path| path.parent()).filter(|path| !path.as_str().is_empty())
+}
+
+fn is_dir(entry: &DirEntry) -> bool {
+    entry.file_type().is_some_and(|ty| ty.is_dir())
+}
+
+fn relative(root: &Path, path: &Path) -> Option<RelativePathBuf> {
+    let path = pathdiff::diff_paths(path, root)?;
+    RelativePathBuf::from_path(path).ok()
+}
@@ -1,7 +1,11 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-use crate::{Body, resolver::FileResolver};
-use futures::{StreamExt, TryStreamExt, pin_mut, stream::BoxStream};
+use crate::{
+    Body,
+    resolver::{FileResolver, SymlinkPolicy},
+    watch::FsWatch,
+};
+use futures::{StreamExt, stream::BoxStream};
 use pipes::{Matcher, Source};
 use pipes_package::Package;
 use relative_path::RelativePathBuf;
@@ -28,6 +32,52 @@ impl FsSource {
             root: self.root.pattern(pattern),
         }
     }
+
+    /// Leave out paths matching `pattern`. Matching directories are not descended into.
+    pub fn exclude<T: Matcher<RelativePathBuf> + 'static>(self, pattern: T) -> Self {
+        Self {
+            root: self.root.exclude(pattern),
+        }
+    }
+
+    /// Respect ignore files with the given name, eg. `.pipesignore`
+    pub fn ignore_file(self, name: impl Into<String>) -> Self {
+        Self {
+            root: self.root.ignore_file(name),
+        }
+    }
+
+    /// Respect `.gitignore` files and skip `.git` directories
+    pub fn git_ignore(self, enable: bool) -> Self {
+        Self {
+            root: self.root.git_ignore(enable),
+        }
+    }
+
+    /// Whether to include hidden files and directories. Defaults to true.
+    pub fn hidden(self, include: bool) -> Self {
+        Self {
+            root: self.root.hidden(include),
+        }
+    }
+
+    pub fn max_depth(self, depth: usize) -> Self {
+        Self {
+            root: self.root.max_depth(depth),
+        }
+    }
+
+    pub fn symlinks(self, policy: SymlinkPolicy) -> Self {
+        Self {
+            root: self.root.symlinks(policy),
+        }
+    }
+
+    /// Yield all matching files, then keep watching the root for changes.
+    /// Errors are yielded without ending the stream.
+    pub fn watch(self) -> FsWatch {
+        FsWatch::new(self.root)
+    }
 }
 
 impl<C> Source<C> for FsSource {
@@ -39,20 +89,20 @@ impl<C> Source<C> for FsSource {
         Self: 'a;
 
     fn create_stream<'a>(self, _ctx: C) -> Self::Stream<'a> {
-        async_stream::try_stream! {
-            let root = self.root.root().to_path_buf();
-
-            let stream = self.root.find();
-            pin_mut!(stream);
-
-            while let Some(next) = stream.try_next().await.map_err(pipes::Error::new)? {
-                let full_path = next.to_logical_path(&root);
-                let mime = mime_guess::from_path(&full_path).first_or_octet_stream();
+        let root = self.root.root().to_path_buf();
 
-                yield Package::new(next, mime, Body::Path(full_path));
-
-            }
-        }
-        .boxed()
+        self.root
+            .find()
+            .map(move |next| {
+                next.map(|next| package(&root, next))
+                    .map_err(pipes::Error::new)
+            })
+            .boxed()
     }
 }
+
+pub(crate) fn package(root: &Path, path: RelativePathBuf) -> Package<Body> {
+    let full_path = path.to_logical_path(root);
+    let mime = mime_guess::from_path(&full_path).first_or_octet_stream();
+    Package::new(path, mime, Body::Path(full_path))
+}
@@ -0,0 +1,43 @@
+use std::fmt;
+
+use pipes::Report;
+
+/// Returned by units finishing a destination after a pipeline, like [`FsDest::clean`](crate::FsDest::clean)
+/// and `ArchiveDest::finish_after`, when any item of the pipeline failed.
+///
+/// These units run the pipeline before their finishing step. The pipeline has to write
+/// through a clone of the same destination, eg. `dest.clean(source.pipe(dest.clone()).unit())`,
+/// so the step knows what was written. The step is skipped unless every item succeeded, see
+/// [`SourceUnit::report`](pipes::SourceUnit::report), as the existing output of failed items
+/// may be the last good one.
+#[derive(Debug)]
+pub struct UnitFailed {
+    report: Report,
+}
+
+impl UnitFailed {
+    pub(crate) fn new(report: Report) -> UnitFailed {
+        UnitFailed { report }
+    }
+
+    pub fn report(&self) -> &Report {
+        &self.report
+    }
+
+    pub fn into_report(self) -> Report {
+        self.report
+    }
+}
+
+impl fmt::Display for UnitFailed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} of {} items failed, the destination was not finished",
+            self.report.failures().len(),
+            self.report.processed()
+        )
+    }
+}
+
+impl std::error::Error for UnitFailed {}
@@ -0,0 +1,251 @@
+use std::{
+    collections::BTreeSet,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use futures::{StreamExt, pin_mut, stream::BoxStream};
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use pipes::{Error, Source};
+use pipes_package::Package;
+use relative_path::{RelativePath, RelativePathBuf};
+
+use crate::{
+    Body,
+    resolver::{FileResolver, IgnoreCache},
+    source::package,
+};
+
+pub enum WatchEvent {
+    /// A file was found by the initial walk, created or modified
+    Changed(Package<Body>),
+    /// A file was removed. Removing a directory yields one event for each file in it.
+    Removed(RelativePathBuf),
+}
+
+impl WatchEvent {
+    pub fn path(&self) -> &RelativePath {
+        match self {
+            WatchEvent::Changed(package) => package.path(),
+            WatchEvent::Removed(path) => path,
+        }
+    }
+}
+
+pub struct FsWatch {
+    resolver: FileResolver,
+    debounce: Duration,
+}
+
+impl FsWatch {
+    pub(crate) fn new(resolver: FileResolver) -> FsWatch {
+        FsWatch {
+            resolver,
+            debounce: Duration::from_millis(100),
+        }
+    }
+
+    /// Wait until no changes have arrived for `duration` before yielding them. Defaults to 100ms.
+    pub fn debounce(mut self, duration: Duration) -> Self {
+        self.debounce = duration;
+        self
+    }
+}
+
+impl<C> Source<C> for FsWatch {
+    type Item = WatchEvent;
+
+    type Stream<'a>
+        = BoxStream<'a, Result<Self::Item, Error>>
+    where
+        Self: 'a;
+
+    fn create_stream<'a>(self, _ctx: C) -> Self::Stream<'a> {
+        // Errors after setup are yielded and watching goes on, a single bad event
+        // or unreadable file should not stop a long running watch
+        async_stream::stream! {
+            let root = self.resolver.root().to_path_buf();
+
+            let (event_root, _watcher, mut rx) = match watch(&root).await {
+                Ok(ret) => ret,
+                Err(err) => {
+                    yield Err(err);
+                    return;
+                }
+            };
+
+            // Paths yielded so far, so a removed directory can be reported file by file
+            let mut known = BTreeSet::new();
+            let filter = Filter::new(self.resolver.clone());
+
+            let stream = self.resolver.find();
+            pin_mut!(stream);
+
+            while let Some(next) = stream.next().await {
+                match next {
+                    Ok(next) if is_file(&root, &next).await => {
+                        known.insert(next.clone());
+                        yield Ok(WatchEvent::Changed(package(&root, next)));
+                    }
+                    Ok(_) => {}
+                    Err(err) => yield Err(Error::new(err)),
+                }
+            }
+
+            while let Some(event) = rx.recv().await {
+                let mut changed = BTreeSet::new();
+                let mut errors = Vec::new();
+                collect(&mut changed, &mut errors, event);
+
+                while let Ok(Some(event)) = tokio::time::timeout(self.debounce, rx.recv()).await {
+                    collect(&mut changed, &mut errors, event);
+                }
+
+                for err in errors {
+                    yield Err(err);
+                }
+
+                let changed = changed
+                    .into_iter()
+                    .filter_map(|path| pathdiff::diff_paths(&path, &event_root))
+                    .filter_map(|path| RelativePathBuf::from_path(path).ok())
+                    .collect::<Vec<_>>();
+
+                for path in &changed {
+                    filter.changed(path);
+                }
+
+                for path in changed {
+
+                    // The event kind is unreliable across platforms, so look at what is on disk now
+                    match tokio::fs::metadata(path.to_logical_path(&root)).await {
+                        Ok(meta) if meta.is_file() => {
+                            if filter.accepts(&path).await {
+                                known.insert(path.clone());
+                                yield Ok(WatchEvent::Changed(package(&root, path)));
+                            }
+                        }
+                        Ok(meta) if meta.is_dir() => {
+                            // A directory moved into the root only produces an event for itself
+                            let stream = self.resolver.find_in(&path);
+                            pin_mut!(stream);
+
+                            while let Some(next) = stream.next().await {
+                                match next {
+                                    Ok(next) if is_file(&root, &next).await => {
+                                        known.insert(next.clone());
+                                        yield Ok(WatchEvent::Changed(package(&root, next)));
+                                    }
+                                    Ok(_) => {}
+                                    Err(err) => yield Err(Error::new(err)),
+                                }
+                            }
+                        }
+                        Ok(_) => {}
+                        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+                            let removed = known
+                                .iter()
+                                .filter(|known| known.starts_with(&path))
+                                .cloned()
+                                .collect::<Vec<_>>();
+
+                            if removed.is_empty() {
+                                // Created and removed again before it was seen
+                                if filter.accepts(&path).await {
+                                    yield Ok(WatchEvent::Removed(path));
+                                }
+                            }
+
+                            for path in removed {
+                                known.remove(&path);
+                                yield Ok(WatchEvent::Removed(path));
+                            }
+                        }
+                        Err(err) => yield Err(Error::new(err)),
+                    }
+                }
+            }
+        }
+        .boxed()
+    }
+}
+
+/// Checks changed paths against the resolver rules off the async runtime, parsing
+/// each ignore file once until it changes
+#[derive(Clone)]
+struct Filter {
+    resolver: Arc<FileResolver>,
+    cache: Arc<Mutex<IgnoreCache>>,
+}
+
+impl Filter {
+    fn new(resolver: FileResolver) -> Filter {
+        Filter {
+            resolver: Arc::new(resolver),
+            cache: Default::default(),
+        }
+    }
+
+    async fn accepts(&self, path: &RelativePath) -> bool {
+        let filter = self.clone();
+        let path = path.to_relative_path_buf();
+
+        tokio::task::spawn_blocking(move || {
+            let mut cache = filter.cache.lock().expect("lock");
+            filter.resolver.accepts_with(&path, false, &mut cache)
+        })
+        .await
+        .unwrap_or(false)
+    }
+
+    fn changed(&self, path: &RelativePath) {
+        let is_ignore_file = path
+            .file_name()
+            .is_some_and(|name| self.resolver.ignore_names().any(|ignore| ignore == name));
+
+        if let (true, Some(dir)) = (is_ignore_file, path.parent()) {
+            self.cache.lock().expect("lock").invalidate(dir);
+        }
+    }
+}
+
+type Events = tokio::sync::mpsc::UnboundedReceiver<notify::Result<notify::Event>>;
+
+/// Start watching `root`. Returns the canonical root events are relative to, and the
+/// watcher which must be kept alive for events to arrive.
+async fn watch(root: &Path) -> Result<(PathBuf, RecommendedWatcher, Events), Error> {
+    // Events carry absolute paths
+    let event_root = tokio::fs::canonicalize(root).await.map_err(Error::new)?;
+
+    let (sx, rx) = tokio::sync::mpsc::unbounded_channel();
+
+    // Watch before walking so changes made during the walk are not lost
+    let mut watcher = notify::recommended_watcher(move |event| {
+        let _ = sx.send(event);
+    })
+    .map_err(Error::new)?;
+    watcher
+        .watch(&event_root, RecursiveMode::Recursive)
+        .map_err(Error::new)?;
+
+    Ok((event_root, watcher, rx))
+}
+
+async fn is_file(root: &Path, path: &RelativePath) -> bool {
+    tokio::fs::metadata(path.to_logical_path(root))
+        .await
+        .is_ok_and(|meta| meta.is_file())
+}
+
+fn collect(
+    changed: &mut BTreeSet<PathBuf>,
+    errors: &mut Vec<Error>,
+    event: notify::Result<notify::Event>,
+) {
+    match event {
+        Ok(event) if !matches!(event.kind, EventKind::Access(_)) => changed.extend(event.paths),
+        Ok(_) => {}
+        Err(err) => errors.push(Error::new(err)),
+    }
+}
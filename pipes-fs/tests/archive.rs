@@ -0,0 +1,172 @@
+#![cfg(feature = "archive")]
+
+use std::path::Path;
+
+use futures::{StreamExt, stream::BoxStream};
+use pipes::{Error, ErrorPolicy, Source, Unit, Work, prelude::*};
+use pipes_fs::{ArchiveDest, ArchiveSource, Body, ExpandArchive, UnitFailed};
+use pipes_package::{Bytes, Package, mime};
+
+fn packages() -> Vec<Result<Package<Bytes>, Error>> {
+    vec![
+        Ok(Package::new(
+            "a.txt",
+            mime::TEXT_PLAIN,
+            Bytes::from("hello ".repeat(100)),
+        )),
+        Ok(Package::new(
+            "img/b.png",
+            mime::IMAGE_PNG,
+            Bytes::from("not really a png"),
+        )),
+    ]
+}
+
+async fn write_archive(dest: ArchiveDest) {
+    let report = dest
+        .finish_after(
+            packages()
+                .pipe(dest.clone())
+                .unit()
+                .report(ErrorPolicy::Continue),
+        )
+        .run(())
+        .await
+        .unwrap();
+    assert!(report.is_success());
+}
+
+async fn read_archive(path: &Path) -> Vec<(String, Bytes)> {
+    ArchiveSource::new(path)
+        .create_stream(())
+        .then(|package| async move {
+            let mut package = package.unwrap();
+            let content = package.content_mut().bytes().await.unwrap();
+            (package.path().to_string(), content)
+        })
+        .collect()
+        .await
+}
+
+fn expected() -> Vec<(String, Bytes)> {
+    vec![
+        ("a.txt".to_string(), Bytes::from("hello ".repeat(100))),
+        ("img/b.png".to_string(), Bytes::from("not really a png")),
+    ]
+}
+
+fn tar_entry(name: &str, claimed_size: u64, content: &[u8]) -> Bytes {
+    let mut header = tar::Header::new_gnu();
+    header.set_path(name).unwrap();
+    header.set_size(claimed_size);
+    header.set_mode(0o644);
+    header.set_cksum();
+
+    let mut bytes = header.as_bytes().to_vec();
+    bytes.extend_from_slice(content);
+    bytes.resize(bytes.len().next_multiple_of(512) + 1024, 0);
+    bytes.into()
+}
+
+async fn expand(work: ExpandArchive, archive: Bytes) -> Vec<Result<Package<Body>, Error>> {
+    let package = Package::new("archive.tar", mime::APPLICATION_OCTET_STREAM, archive);
+    let stream: BoxStream<'static, _> = work.call((), package).await.unwrap();
+    stream.collect().await
+}
+
+#[tokio::test]
+async fn header_size_is_not_trusted() {
+    let archive = tar_entry("big.txt", 8 << 30, b"small");
+
+    let entries = expand(ExpandArchive::new(), archive.clone()).await;
+    assert!(matches!(entries.as_slice(), [Err(_)]));
+
+    // Without a limit the claimed size is not allocated up front either
+    let entries = expand(ExpandArchive::new().max_entry_size(u64::MAX), archive).await;
+    assert!(entries.iter().any(|entry| entry.is_err()));
+}
+
+#[tokio::test]
+async fn entries_larger_than_the_limit_fail() {
+    let archive = tar_entry("hello.txt", 5, b"hello");
+
+    let entries = expand(ExpandArchive::new().max_entry_size(5), archive.clone()).await;
+    assert!(matches!(entries.as_slice(), [Ok(package)] if package.path() == "hello.txt"));
+
+    let entries = expand(ExpandArchive::new().max_entry_size(4), archive).await;
+    assert!(matches!(entries.as_slice(), [Err(_)]));
+}
+
+#[tokio::test]
+async fn zip_round_trip() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("out.zip");
+
+    write_archive(ArchiveDest::zip(&path)).await;
+    assert_eq!(read_archive(&path).await, expected());
+
+    // Already compressed content is stored, everything else deflated
+    let mut zip = zip::ZipArchive::new(std::fs::File::open(&path).unwrap()).unwrap();
+    assert_eq!(
+        zip.by_name("a.txt").unwrap().compression(),
+        zip::CompressionMethod::Deflated
+    );
+    assert_eq!(
+        zip.by_name("img/b.png").unwrap().compression(),
+        zip::CompressionMethod::Stored
+    );
+}
+
+#[tokio::test]
+async fn tar_gz_round_trip() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("out.tar.gz");
+
+    write_archive(ArchiveDest::tar_gz(&path)).await;
+    assert_eq!(read_archive(&path).await, expected());
+}
+
+#[tokio::test]
+async fn failed_items_discard_the_archive() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("out.zip");
+    std::fs::write(&path, "previous").unwrap();
+
+    let mut source = packages();
+    source.push(Err(Error::new("failed")));
+
+    let dest = ArchiveDest::zip(&path);
+    let err = dest
+        .finish_after(
+            source
+                .pipe(dest.clone())
+                .unit()
+                .report(ErrorPolicy::Continue),
+        )
+        .run(())
+        .await
+        .unwrap_err();
+
+    let skipped = err.downcast_ref::<UnitFailed>().unwrap();
+    assert_eq!(skipped.report().failures().len(), 1);
+    assert_eq!(std::fs::read(&path).unwrap(), b"previous");
+    // The temporary archive is removed
+    assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+}
+
+#[tokio::test]
+async fn unfinished_archive_is_removed() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("out.tar");
+
+    let dest = ArchiveDest::tar(&path);
+    packages()
+        .pipe(dest.clone())
+        .unit()
+        .report(ErrorPolicy::Continue)
+        .run(())
+        .await;
+    drop(dest);
+
+    assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+}
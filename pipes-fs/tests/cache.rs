@@ -0,0 +1,244 @@
+use std::{
+    path::Path,
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+};
+
+use futures::future::BoxFuture;
+use pipes::{Error, Work, WorkExt, work_fn};
+use pipes_fs::Cache;
+use pipes_img::{Format, ImageWork, save};
+use pipes_package::{Bytes, Package, mime};
+
+// A 1x1 png
+const PNG: &[u8] = &[
+    0x89, 0x50, 0x4e, 0x47, 0x0d, 0x0a, 0x1a, 0x0a, 0x00, 0x00, 0x00, 0x0d, 0x49, 0x48, 0x44, 0x52,
+    0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x01, 0x08, 0x02, 0x00, 0x00, 0x00, 0x90, 0x77, 0x53,
+    0xde, 0x00, 0x00, 0x00, 0x0c, 0x49, 0x44, 0x41, 0x54, 0x78, 0x9c, 0x63, 0xf8, 0xcf, 0xc0, 0x00,
+    0x00, 0x03, 0x01, 0x01, 0x00, 0xc9, 0xfe, 0x92, 0xef, 0x00, 0x00, 0x00, 0x00, 0x49, 0x45, 0x4e,
+    0x44, 0xae, 0x42, 0x60, 0x82,
+];
+
+#[derive(Debug, Clone, PartialEq)]
+struct Marker;
+
+fn input(content: &'static str) -> Package<Bytes> {
+    Package::new("a.txt", mime::TEXT_PLAIN, Bytes::from(content))
+}
+
+/// Uppercases the content and renames it to `.md`, counting its calls
+struct Upper(Arc<AtomicUsize>);
+
+impl Work<(), Package<Bytes>> for Upper {
+    type Output = Package<Bytes>;
+
+    type Future<'a> = BoxFuture<'a, Result<Package<Bytes>, Error>>;
+
+    fn call<'a>(&'a self, _ctx: (), mut package: Package<Bytes>) -> Self::Future<'a> {
+        self.0.fetch_add(1, Ordering::SeqCst);
+        Box::pin(async move {
+            let content = package.content().to_ascii_uppercase();
+            package.replace_content(content.into());
+            package.path_mut().set_extension("md");
+            package.set_mime(mime::TEXT_HTML);
+            Ok(package)
+        })
+    }
+}
+
+fn entries(dir: &Path) -> Vec<std::path::PathBuf> {
+    let mut entries = Vec::new();
+    for shard in std::fs::read_dir(dir).unwrap() {
+        for entry in std::fs::read_dir(shard.unwrap().path()).unwrap() {
+            entries.push(entry.unwrap().path());
+        }
+    }
+    entries
+}
+
+#[tokio::test]
+async fn hit_reuses_the_output() {
+    let dir = tempfile::tempdir().unwrap();
+    let cache = Cache::new(dir.path());
+    let calls = Arc::new(AtomicUsize::new(0));
+    let work = cache.cached("upper", Upper(calls.clone()));
+
+    let miss = work.call((), input("hello")).await.unwrap();
+    let hit = work.call((), input("hello")).await.unwrap();
+
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+    for output in [&miss, &hit] {
+        assert_eq!(output.path(), "a.md");
+        assert_eq!(output.mime(), &mime::TEXT_HTML);
+        assert_eq!(output.content(), &Bytes::from("HELLO"));
+    }
+
+    // Changed content is a miss
+    let output = work.call((), input("other")).await.unwrap();
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+    assert_eq!(output.content(), &Bytes::from("OTHER"));
+}
+
+#[tokio::test]
+async fn fingerprint_change_invalidates() {
+    let dir = tempfile::tempdir().unwrap();
+    let cache = Cache::new(dir.path());
+    let calls = Arc::new(AtomicUsize::new(0));
+
+    cache
+        .cached("upper v1", Upper(calls.clone()))
+        .call((), input("hello"))
+        .await
+        .unwrap();
+    cache
+        .cached("upper v2", Upper(calls.clone()))
+        .call((), input("hello"))
+        .await
+        .unwrap();
+
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn corrupt_entry_is_a_miss() {
+    let dir = tempfile::tempdir().unwrap();
+    let cache = Cache::new(dir.path());
+    let calls = Arc::new(AtomicUsize::new(0));
+    let work = cache.cached("upper", Upper(calls.clone()));
+
+    work.call((), input("hello")).await.unwrap();
+    for entry in entries(dir.path()) {
+        std::fs::write(entry, "garbage").unwrap();
+    }
+
+    let output = work.call((), input("hello")).await.unwrap();
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+    assert_eq!(output.content(), &Bytes::from("HELLO"));
+
+    // The entry was rewritten
+    work.call((), input("hello")).await.unwrap();
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn stages_adding_meta_are_not_cached() {
+    let dir = tempfile::tempdir().unwrap();
+    let cache = Cache::new(dir.path());
+    let calls = Arc::new(AtomicUsize::new(0));
+    let work = cache.cached(
+        "marked",
+        Upper(calls.clone()).pipe(work_fn(
+            |_ctx: (), mut package: Package<Bytes>| async move {
+                package.meta_mut().insert(Marker);
+                Result::<_, Error>::Ok(package)
+            },
+        )),
+    );
+
+    for _ in 0..2 {
+        let output = work.call((), input("hello")).await.unwrap();
+        assert_eq!(output.meta().get::<Marker>(), Some(&Marker));
+    }
+
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+    assert!(!dir.path().exists() || entries(dir.path()).is_empty());
+}
+
+#[tokio::test]
+async fn image_decode_and_save() {
+    let dir = tempfile::tempdir().unwrap();
+    let cache = Cache::new(dir.path());
+    let work = cache.cached(
+        "save png",
+        WorkExt::<(), Package<Bytes>>::pipe(ImageWork::default(), save(Format::Png)),
+    );
+
+    let image = || Package::new("img.jpg", mime::IMAGE_PNG, Bytes::from_static(PNG));
+
+    let miss = work.call((), image()).await.unwrap();
+    assert_eq!(entries(dir.path()).len(), 1);
+
+    let hit = work.call((), image()).await.unwrap();
+    assert_eq!(hit.path(), "img.png");
+    assert_eq!(hit.path(), miss.path());
+    assert_eq!(hit.content(), miss.content());
+}
+
+#[cfg(feature = "serde")]
+mod meta_values {
+    use super::*;
+    use pipes_package::MetaKey;
+
+    const TITLE: MetaKey<String> = MetaKey::serde("title");
+
+    /// Runs `f` on every package, counting its calls
+    struct Counted<F>(Arc<AtomicUsize>, F);
+
+    impl<F> Work<(), Package<Bytes>> for Counted<F>
+    where
+        F: Fn(&mut Package<Bytes>) + Sync,
+    {
+        type Output = Package<Bytes>;
+
+        type Future<'a>
+            = BoxFuture<'a, Result<Package<Bytes>, Error>>
+        where
+            F: 'a;
+
+        fn call<'a>(&'a self, _ctx: (), mut package: Package<Bytes>) -> Self::Future<'a> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            (self.1)(&mut package);
+            Box::pin(async move { Ok(package) })
+        }
+    }
+
+    fn titled(title: &str) -> Package<Bytes> {
+        let mut package = input("body");
+        package.meta_mut().insert_key(&TITLE, title.to_string());
+        package
+    }
+
+    #[tokio::test]
+    async fn input_meta_is_part_of_the_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = Cache::new(dir.path());
+        let calls = Arc::new(AtomicUsize::new(0));
+        let work = cache.cached(
+            "template",
+            Counted(calls.clone(), |package: &mut Package<Bytes>| {
+                let title = package.meta().get_key(&TITLE).cloned().unwrap_or_default();
+                package.replace_content(Bytes::from(title));
+            }),
+        );
+
+        for (title, calls_after) in [("A", 1), ("B", 2), ("A", 2)] {
+            let output = work.call((), titled(title)).await.unwrap();
+            assert_eq!(output.content(), &Bytes::from(title.to_string()));
+            assert_eq!(calls.load(Ordering::SeqCst), calls_after);
+        }
+    }
+
+    #[tokio::test]
+    async fn stages_changing_meta_values_are_not_cached() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = Cache::new(dir.path());
+        let calls = Arc::new(AtomicUsize::new(0));
+        let work = cache.cached(
+            "upper title",
+            Counted(calls.clone(), |package: &mut Package<Bytes>| {
+                if let Some(title) = package.meta_mut().get_key_mut(&TITLE) {
+                    *title = title.to_uppercase();
+                }
+            }),
+        );
+
+        for _ in 0..2 {
+            let output = work.call((), titled("a")).await.unwrap();
+            assert_eq!(output.meta().get_key(&TITLE).map(String::as_str), Some("A"));
+        }
+
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+}
@@ -0,0 +1,71 @@
+use std::path::Path;
+
+use pipes::{Error, ErrorPolicy, Unit, prelude::*, work_fn};
+use pipes_fs::{Body, FsDest, MemoryFs, UnitFailed};
+use pipes_package::{Bytes, Package};
+
+fn write_previous_output(root: &Path) {
+    for name in ["a.txt", "b.txt", "c.txt", "stale.txt"] {
+        std::fs::write(root.join(name), "old").unwrap();
+    }
+}
+
+fn input() -> MemoryFs {
+    MemoryFs::new()
+        .with_file("a.txt", "new")
+        .with_file("b.txt", "new")
+        .with_file("c.txt", "new")
+}
+
+#[tokio::test]
+async fn clean_removes_stale_files_after_success() {
+    let out = tempfile::tempdir().unwrap();
+    write_previous_output(out.path());
+
+    let dest = FsDest::new(out.path());
+    let unit = input()
+        .source()
+        .pipe(work_fn(|_ctx: (), package: Package<Bytes>| async move {
+            let content = package.content().clone();
+            Result::<_, Error>::Ok(package.map_content(Body::from(content)))
+        }))
+        .pipe(dest.clone())
+        .unit()
+        .report(ErrorPolicy::Continue);
+
+    let (report, removed) = dest.clean(unit).run(()).await.unwrap();
+
+    assert!(report.is_success());
+    assert_eq!(removed, vec![out.path().join("stale.txt")]);
+    assert!(!out.path().join("stale.txt").exists());
+    assert_eq!(std::fs::read(out.path().join("b.txt")).unwrap(), b"new");
+}
+
+#[tokio::test]
+async fn clean_keeps_everything_when_an_item_fails() {
+    let out = tempfile::tempdir().unwrap();
+    write_previous_output(out.path());
+
+    let dest = FsDest::new(out.path());
+    let unit = input()
+        .source()
+        .pipe(work_fn(|_ctx: (), package: Package<Bytes>| async move {
+            if package.path() != "a.txt" {
+                return Err(Error::new("failed"));
+            }
+            let content = package.content().clone();
+            Ok(package.map_content(Body::from(content)))
+        }))
+        .pipe(dest.clone())
+        .unit()
+        .report(ErrorPolicy::FailFast);
+
+    let err = dest.clean(unit).run(()).await.unwrap_err();
+
+    let skipped = err.downcast_ref::<UnitFailed>().unwrap();
+    assert_eq!(skipped.report().failures().len(), 1);
+    for name in ["a.txt", "b.txt", "c.txt", "stale.txt"] {
+        assert!(out.path().join(name).exists(), "{name} was removed");
+    }
+    assert_eq!(std::fs::read(out.path().join("b.txt")).unwrap(), b"old");
+}
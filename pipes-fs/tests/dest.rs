@@ -0,0 +1,65 @@
+use futures::StreamExt;
+use pipes::{Error, Source, SourceExt, Work};
+use pipes_fs::{Body, FileExists, FsDest, OverwritePolicy, WriteAction, WriteReport};
+use pipes_package::Package;
+
+fn same_path_twice() -> Vec<Result<Package<Body>, Error>> {
+    ["first", "second"]
+        .into_iter()
+        .map(|content| {
+            Ok(Package::new(
+                "a.txt",
+                pipes_fs::mime::TEXT_PLAIN,
+                Body::from(pipes_package::Bytes::from(content)),
+            ))
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn empty_body_overwrites_existing_file() {
+    let out = tempfile::tempdir().unwrap();
+
+    for dest in [FsDest::new(out.path()), FsDest::new(out.path()).streaming()] {
+        std::fs::write(out.path().join("a.txt"), "old").unwrap();
+
+        let package = Package::new("a.txt", pipes_fs::mime::TEXT_PLAIN, Body::Empty);
+        let package = dest.call((), package).await.unwrap();
+
+        let report = package.meta().get::<WriteReport>().unwrap();
+        assert_eq!(report.action, WriteAction::Overwrite);
+        assert_eq!(std::fs::read(out.path().join("a.txt")).unwrap(), b"");
+    }
+}
+
+#[tokio::test]
+async fn concurrent_writes_to_one_path_create_it_once() {
+    let out = tempfile::tempdir().unwrap();
+    let dest = FsDest::new(out.path()).policy(OverwritePolicy::ErrorIfExists);
+
+    let output = same_path_twice()
+        .pipe_concurrent(dest, 4)
+        .create_stream(())
+        .collect::<Vec<_>>()
+        .await;
+
+    let errors = output
+        .into_iter()
+        .filter_map(|ret| ret.err())
+        .collect::<Vec<_>>();
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].downcast_ref::<FileExists>().is_some());
+
+    let out = tempfile::tempdir().unwrap();
+    let dest = FsDest::new(out.path()).policy(OverwritePolicy::SkipIfExists);
+
+    let mut actions = same_path_twice()
+        .pipe_concurrent(dest, 4)
+        .create_stream(())
+        .map(|ret| ret.unwrap().meta().get::<WriteReport>().unwrap().action)
+        .collect::<Vec<_>>()
+        .await;
+    actions.sort_by_key(|action| *action == WriteAction::Skip);
+
+    assert_eq!(actions, vec![WriteAction::Create, WriteAction::Skip]);
+}
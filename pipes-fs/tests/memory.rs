@@ -0,0 +1,38 @@
+use pipes::{ErrorPolicy, Unit, prelude::*, work_fn};
+use pipes_fs::MemoryFs;
+use pipes_package::{Bytes, Package};
+
+#[tokio::test]
+async fn memory_fs_as_source_and_dest() {
+    let input = MemoryFs::new()
+        .with_file("a.txt", "hello")
+        .with_file("dir/b.txt", "world");
+    let output = MemoryFs::new();
+
+    let report = input
+        .source()
+        .pipe(work_fn(
+            |_ctx: (), mut package: Package<Bytes>| async move {
+                let content = package.content().to_ascii_uppercase();
+                package.replace_content(content.into());
+                package.set_path(format!("out/{}", package.path()));
+                Result::<_, pipes::Error>::Ok(package)
+            },
+        ))
+        .pipe(output.dest())
+        .unit()
+        .report(ErrorPolicy::FailFast)
+        .run(())
+        .await;
+
+    assert!(report.is_success());
+    assert_eq!(output.written(), vec!["out/a.txt", "out/dir/b.txt"]);
+    assert_eq!(output.read("out/a.txt").unwrap(), "HELLO");
+    assert_eq!(output.read("out/dir/b.txt").unwrap(), "WORLD");
+    assert_eq!(
+        output.mime("out/a.txt").unwrap(),
+        pipes_fs::mime::TEXT_PLAIN
+    );
+    // The source is left untouched
+    assert_eq!(input.paths(), vec!["a.txt", "dir/b.txt"]);
+}
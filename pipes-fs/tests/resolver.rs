@@ -0,0 +1,156 @@
+use std::path::Path;
+
+use futures::StreamExt;
+use pipes::{MatcherExt, Source};
+use pipes_fs::{FsSource, SymlinkPolicy};
+use pipes_package::match_glob;
+
+fn write(root: &Path, path: &str, content: &str) {
+    let path = root.join(path);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, content).unwrap();
+}
+
+/// Paths of the files yielded, sorted, along with the number of errors
+async fn files(source: FsSource, root: &Path) -> (Vec<String>, usize) {
+    let items = source.create_stream(()).collect::<Vec<_>>().await;
+
+    let errors = items.iter().filter(|item| item.is_err()).count();
+    let mut files = items
+        .into_iter()
+        .flatten()
+        .map(|package| package.path().to_string())
+        .filter(|path| root.join(path).is_file())
+        .collect::<Vec<_>>();
+    files.sort();
+
+    (files, errors)
+}
+
+#[tokio::test]
+async fn ignore_file_with_negation() {
+    let root = tempfile::tempdir().unwrap();
+    write(root.path(), ".pipesignore", "*.log\n!keep.log\n");
+    write(root.path(), "a.log", "");
+    write(root.path(), "sub/keep.log", "");
+    write(root.path(), "a.txt", "");
+
+    let source = FsSource::new(root.path().to_path_buf()).ignore_file(".pipesignore");
+
+    assert_eq!(
+        files(source, root.path()).await,
+        (
+            vec![".pipesignore".into(), "a.txt".into(), "sub/keep.log".into()],
+            0
+        )
+    );
+}
+
+#[tokio::test]
+async fn git_and_excluded_directories_are_pruned() {
+    let root = tempfile::tempdir().unwrap();
+    write(root.path(), ".git/HEAD", "");
+    write(root.path(), "node_modules/x/a.js", "");
+    write(root.path(), "src/a.js", "");
+
+    let source = FsSource::new(root.path().to_path_buf())
+        .git_ignore(true)
+        .exclude(match_glob("node_modules"));
+
+    assert_eq!(
+        files(source, root.path()).await,
+        (vec!["src/a.js".into()], 0)
+    );
+}
+
+#[tokio::test]
+async fn max_depth_of_one_yields_the_root_entries() {
+    let root = tempfile::tempdir().unwrap();
+    write(root.path(), "a.txt", "");
+    write(root.path(), "sub/b.txt", "");
+
+    let source = FsSource::new(root.path().to_path_buf()).max_depth(1);
+
+    assert_eq!(files(source, root.path()).await, (vec!["a.txt".into()], 0));
+}
+
+#[tokio::test]
+async fn hidden_files_are_left_out() {
+    let root = tempfile::tempdir().unwrap();
+    write(root.path(), ".hidden.txt", "");
+    write(root.path(), ".dir/a.txt", "");
+    write(root.path(), "a.txt", "");
+
+    let source = FsSource::new(root.path().to_path_buf()).hidden(false);
+
+    assert_eq!(files(source, root.path()).await, (vec!["a.txt".into()], 0));
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn skipped_symlinks() {
+    let root = tempfile::tempdir().unwrap();
+    write(root.path(), "a.txt", "");
+    write(root.path(), "dir/b.txt", "");
+    std::os::unix::fs::symlink(root.path().join("a.txt"), root.path().join("link.txt")).unwrap();
+    std::os::unix::fs::symlink(root.path().join("dir"), root.path().join("linked")).unwrap();
+
+    let source = FsSource::new(root.path().to_path_buf());
+    assert_eq!(
+        files(source, root.path()).await,
+        (
+            vec!["a.txt".into(), "dir/b.txt".into(), "link.txt".into()],
+            0
+        )
+    );
+
+    let source = FsSource::new(root.path().to_path_buf()).symlinks(SymlinkPolicy::Skip);
+    assert_eq!(
+        files(source, root.path()).await,
+        (vec!["a.txt".into(), "dir/b.txt".into()], 0)
+    );
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn walk_errors_do_not_end_the_walk() {
+    let root = tempfile::tempdir().unwrap();
+    for dir in ["a", "b", "c", "d"] {
+        write(root.path(), &format!("{dir}/file.txt"), "");
+    }
+    // Following this link leads back to the root, which the walker reports as an error
+    std::os::unix::fs::symlink(root.path(), root.path().join("a/loop")).unwrap();
+
+    let source = FsSource::new(root.path().to_path_buf()).symlinks(SymlinkPolicy::Follow);
+
+    assert_eq!(
+        files(source, root.path()).await,
+        (
+            vec![
+                "a/file.txt".into(),
+                "b/file.txt".into(),
+                "c/file.txt".into(),
+                "d/file.txt".into()
+            ],
+            1
+        )
+    );
+}
+
+#[tokio::test]
+async fn combined_glob_patterns() {
+    let root = tempfile::tempdir().unwrap();
+    write(root.path(), "index.md", "");
+    write(root.path(), "posts/a.md", "");
+    write(root.path(), "posts/a.txt", "");
+    write(root.path(), "drafts/b.md", "");
+    write(root.path(), "drafts/sub/c.md", "");
+
+    let source = FsSource::new(root.path().to_path_buf())
+        .pattern(match_glob("**/*.md").and(match_glob("drafts/**").not()));
+
+    assert_eq!(
+        files(source, root.path()).await,
+        (vec!["index.md".into(), "posts/a.md".into()], 0)
+    );
+}
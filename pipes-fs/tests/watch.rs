@@ -0,0 +1,108 @@
+use std::time::Duration;
+
+use futures::{StreamExt, stream::BoxStream};
+use pipes::{Error, Source};
+use pipes_fs::{FsSource, WatchEvent};
+use pipes_package::match_glob;
+
+async fn next_event(stream: &mut BoxStream<'_, Result<WatchEvent, Error>>) -> (bool, String) {
+    let event = tokio::time::timeout(Duration::from_secs(5), stream.next())
+        .await
+        .expect("timed out waiting for event")
+        .expect("stream ended")
+        .expect("watch error");
+
+    let changed = matches!(event, WatchEvent::Changed(_));
+    (changed, event.path().to_string())
+}
+
+#[tokio::test]
+async fn directory_moves_yield_their_files() {
+    let root = tempfile::tempdir().unwrap();
+    let outside = tempfile::tempdir().unwrap();
+
+    std::fs::create_dir(root.path().join("old")).unwrap();
+    std::fs::write(root.path().join("old/a.md"), "a").unwrap();
+    std::fs::create_dir(outside.path().join("sub")).unwrap();
+    std::fs::write(outside.path().join("sub/b.md"), "b").unwrap();
+
+    let mut stream = FsSource::new(root.path().to_path_buf())
+        .pattern(match_glob("**/*.md"))
+        .watch()
+        .debounce(Duration::from_millis(50))
+        .create_stream(());
+
+    assert_eq!(
+        next_event(&mut stream).await,
+        (true, "old/a.md".to_string())
+    );
+
+    std::fs::rename(outside.path().join("sub"), root.path().join("sub")).unwrap();
+    assert_eq!(
+        next_event(&mut stream).await,
+        (true, "sub/b.md".to_string())
+    );
+
+    std::fs::rename(root.path().join("old"), outside.path().join("old")).unwrap();
+    assert_eq!(
+        next_event(&mut stream).await,
+        (false, "old/a.md".to_string())
+    );
+}
+
+#[tokio::test]
+async fn initial_walk_yields_only_files() {
+    let root = tempfile::tempdir().unwrap();
+
+    std::fs::write(root.path().join("a.txt"), "a").unwrap();
+    std::fs::create_dir(root.path().join("sub")).unwrap();
+    std::fs::write(root.path().join("sub/b.txt"), "b").unwrap();
+
+    let mut stream = FsSource::new(root.path().to_path_buf())
+        .watch()
+        .debounce(Duration::from_millis(50))
+        .create_stream(());
+
+    let mut initial = vec![next_event(&mut stream).await, next_event(&mut stream).await];
+    initial.sort();
+    assert_eq!(
+        initial,
+        vec![(true, "a.txt".to_string()), (true, "sub/b.txt".to_string())]
+    );
+
+    std::fs::write(root.path().join("c.txt"), "c").unwrap();
+    assert_eq!(next_event(&mut stream).await, (true, "c.txt".to_string()));
+}
+
+#[tokio::test]
+async fn changed_ignore_files_are_read_again() {
+    let root = tempfile::tempdir().unwrap();
+    std::fs::write(root.path().join(".pipesignore"), "*.log\n").unwrap();
+
+    let mut stream = FsSource::new(root.path().to_path_buf())
+        .ignore_file(".pipesignore")
+        .watch()
+        .debounce(Duration::from_millis(50))
+        .create_stream(());
+
+    assert_eq!(
+        next_event(&mut stream).await,
+        (true, ".pipesignore".to_string())
+    );
+
+    std::fs::write(root.path().join("a.log"), "a").unwrap();
+    std::fs::write(root.path().join("b.txt"), "b").unwrap();
+    assert_eq!(next_event(&mut stream).await, (true, "b.txt".to_string()));
+
+    std::fs::write(root.path().join(".pipesignore"), "").unwrap();
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    std::fs::write(root.path().join("c.log"), "c").unwrap();
+
+    loop {
+        let event = next_event(&mut stream).await;
+        if event.1 != ".pipesignore" {
+            assert_eq!(event, (true, "c.log".to_string()));
+            break;
+        }
+    }
+}
@@ -0,0 +1,27 @@
+use bytes::{BufMut, BytesMut};
+use pipes::Error;
+
+use crate::{Content, Package};
+
+/// Fold function appending the content of each package to `bundle`, one per line.
+///
+/// The bundle is accumulated in a [`BytesMut`] so each package is copied once.
+///
+/// ```ignore
+/// source.fold(Package::new("bundle.css", mime::TEXT_CSS, BytesMut::new()), concat)
+/// ```
+pub async fn concat<C, B: Content>(
+    _ctx: C,
+    mut bundle: Package<BytesMut>,
+    mut package: Package<B>,
+) -> Result<Package<BytesMut>, Error> {
+    let content = package.content_mut().bytes().await?;
+
+    let bytes = bundle.content_mut();
+    if !bytes.is_empty() && !bytes.ends_with(b"\n") {
+        bytes.put_u8(b'\n');
+    }
+    bytes.put(content);
+
+    Ok(bundle)
+}
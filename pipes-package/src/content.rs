@@ -15,6 +15,14 @@ impl Content for Bytes {
     }
 }
 
+/// Copies the buffer on every call, freeze it first to share it instead
+#[async_trait]
+impl Content for BytesMut {
+    async fn bytes(&mut self) -> Result<Bytes, Error> {
+        Ok(Bytes::copy_from_slice(self))
+    }
+}
+
 enum StreamContentState<T> {
     Stream(T),
     Bytes(Bytes),
@@ -1,8 +1,9 @@
 use std::marker::PhantomData;
 
-use pipes::Work;
+use pipes::{ConflictPolicy, Distinct, Source, SourceExt as _, Work};
+use relative_path::RelativePathBuf;
 
-use crate::{IntoPackage, into_package::IntoPackageWork};
+use crate::{IntoPackage, Package, by_path, into_package::IntoPackageWork, trace_path::TracePath};
 
 pub trait WorkExt<C, T>: Work<C, T> {
     fn into_package<B>(self) -> IntoPackageWork<Self, C, B>
@@ -15,6 +16,26 @@ pub trait WorkExt<C, T>: Work<C, T> {
             ctx: PhantomData,
         }
     }
+
+    /// Attach the path of the input package to errors returned by this work
+    fn trace_path(self) -> TracePath<Self>
+    where
+        Self: Sized,
+    {
+        TracePath { worker: self }
+    }
 }
 
 impl<C, T, W> WorkExt<C, T> for W where W: Work<C, T> {}
+
+pub trait SourceExt<C, B>: Source<C, Item = Package<B>> {
+    /// Remove packages with duplicate paths, e.g. when merging several sources
+    fn distinct(self, policy: ConflictPolicy) -> Distinct<Self, fn(&Package<B>) -> RelativePathBuf>
+    where
+        Self: Sized,
+    {
+        self.distinct_by(by_path as fn(&Package<B>) -> RelativePathBuf, policy)
+    }
+}
+
+impl<C, B, S> SourceExt<C, B> for S where S: Source<C, Item = Package<B>> {}
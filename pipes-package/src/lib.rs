@@ -1,14 +1,18 @@
+mod concat;
 mod content;
 mod ext;
 mod into_package;
 mod matcher;
 mod package;
+mod trace_path;
 
 pub use self::{
+    concat::concat,
     content::*,
     into_package::{IntoPackageWork, IntoPackageWorkFuture},
     matcher::*,
-    package::{IntoPackage, Meta, Package},
+    package::{IntoPackage, Meta, MetaKey, Package, by_parent, by_path},
+    trace_path::{TracePath, TracePathFuture},
 };
 pub mod prelude {
     pub use super::ext::*;
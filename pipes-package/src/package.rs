@@ -1,8 +1,13 @@
 use core::{
     any::{Any, TypeId},
+    cell::OnceCell,
+    marker::PhantomData,
     task::Poll,
 };
-use std::collections::HashMap;
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, HashMap},
+};
 
 use either::Either;
 use futures::{Future, future::BoxFuture};
@@ -42,9 +47,165 @@ where
     }
 }
 
+/// Named key for a value of type `T` in [`Meta`]
+///
+/// Unlike the type keyed values, two keys holding the same type do not collide
+/// as long as their names differ. Keys created with [`MetaKey::serde`] are
+/// included when serializing [`Meta`].
+pub struct MetaKey<T> {
+    name: &'static str,
+    #[cfg(feature = "serde")]
+    codec: Option<Codec>,
+    _type: PhantomData<fn() -> T>,
+}
+
+impl<T> MetaKey<T> {
+    pub const fn new(name: &'static str) -> MetaKey<T> {
+        MetaKey {
+            name,
+            #[cfg(feature = "serde")]
+            codec: None,
+            _type: PhantomData,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T> MetaKey<T>
+where
+    T: serde::Serialize + serde::de::DeserializeOwned + Clone + Send + 'static,
+{
+    /// Key whose value is serialized with [`Meta`] and decoded again on first access
+    pub const fn serde(name: &'static str) -> MetaKey<T> {
+        MetaKey {
+            name,
+            codec: Some(Codec {
+                encode: encode_value::<T>,
+                decode: decode_value::<T>,
+            }),
+            _type: PhantomData,
+        }
+    }
+}
+
+impl<T> Clone for MetaKey<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for MetaKey<T> {}
+
+impl<T> core::fmt::Debug for MetaKey<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_tuple("MetaKey").field(&self.name).finish()
+    }
+}
+
+#[cfg(feature = "serde")]
+#[derive(Clone, Copy)]
+struct Codec {
+    encode: fn(&dyn Any) -> Option<Result<serde_json::Value, serde_json::Error>>,
+    decode: fn(&serde_json::Value) -> Result<Box<dyn ToAny + Send>, serde_json::Error>,
+}
+
+#[cfg(feature = "serde")]
+fn encode_value<T: serde::Serialize + 'static>(
+    value: &dyn Any,
+) -> Option<Result<serde_json::Value, serde_json::Error>> {
+    value.downcast_ref::<T>().map(serde_json::to_value)
+}
+
+#[cfg(feature = "serde")]
+fn decode_value<T: serde::de::DeserializeOwned + Clone + Send + 'static>(
+    value: &serde_json::Value,
+) -> Result<Box<dyn ToAny + Send>, serde_json::Error> {
+    Ok(Box::new(T::deserialize(value)?))
+}
+
+struct Entry {
+    value: OnceCell<Box<dyn ToAny + Send>>,
+    #[cfg(feature = "serde")]
+    codec: Option<Codec>,
+    /// Serialized value not yet decoded by a typed key
+    #[cfg(feature = "serde")]
+    raw: Option<serde_json::Value>,
+}
+
+impl Entry {
+    #[cfg_attr(not(feature = "serde"), allow(unused_variables))]
+    fn new<T: Clone + Send + 'static>(key: &MetaKey<T>, value: T) -> Entry {
+        Entry {
+            value: OnceCell::from(Box::new(value) as Box<dyn ToAny + Send>),
+            #[cfg(feature = "serde")]
+            codec: key.codec,
+            #[cfg(feature = "serde")]
+            raw: None,
+        }
+    }
+
+    #[cfg_attr(not(feature = "serde"), allow(unused_variables))]
+    fn value<T>(&self, key: &MetaKey<T>) -> Option<&dyn ToAny> {
+        #[cfg(feature = "serde")]
+        if self.value.get().is_none()
+            && let (Some(raw), Some(codec)) = (&self.raw, key.codec)
+            && let Ok(value) = (codec.decode)(raw)
+        {
+            let _ = self.value.set(value);
+        }
+
+        self.value.get().map(|m| &**m as &dyn ToAny)
+    }
+
+    /// Take the value out, decoding it first if needed
+    fn into_value<T: 'static>(self, key: &MetaKey<T>) -> Option<T> {
+        self.value(key)?;
+        self.value
+            .into_inner()
+            .and_then(|m| m.any_box().downcast().ok().map(|m| *m))
+    }
+
+    fn value_mut<T: 'static>(&mut self, key: &MetaKey<T>) -> Option<&mut T> {
+        if !self.value(key)?.as_any().is::<T>() {
+            return None;
+        }
+        #[cfg(feature = "serde")]
+        {
+            // The value may change from here on, so the serialized form is stale
+            self.raw = None;
+            self.codec = key.codec;
+        }
+        self.value
+            .get_mut()
+            .and_then(|m| m.as_any_mut().downcast_mut::<T>())
+    }
+}
+
+impl Clone for Entry {
+    fn clone(&self) -> Self {
+        let value = OnceCell::new();
+        if let Some(v) = self.value.get() {
+            let _ = value.set(v.clone_box());
+        }
+
+        Entry {
+            value,
+            #[cfg(feature = "serde")]
+            codec: self.codec,
+            #[cfg(feature = "serde")]
+            raw: self.raw.clone(),
+        }
+    }
+}
+
 #[derive(Default)]
 pub struct Meta {
     values: HashMap<TypeId, Box<dyn ToAny + Send>>,
+    named: BTreeMap<Cow<'static, str>, Entry>,
 }
 
 impl Meta {
@@ -64,6 +225,47 @@ impl Meta {
             .get_mut(&TypeId::of::<T>())
             .and_then(|m| m.as_any_mut().downcast_mut::<T>())
     }
+
+    /// Insert a value under a named key, returning the previous value if it had the same type
+    pub fn insert_key<T: Clone + Send + 'static>(
+        &mut self,
+        key: &MetaKey<T>,
+        value: T,
+    ) -> Option<T> {
+        let old = self
+            .named
+            .insert(Cow::Borrowed(key.name), Entry::new(key, value))?;
+        old.into_value(key)
+    }
+
+    pub fn get_key<T: 'static>(&self, key: &MetaKey<T>) -> Option<&T> {
+        self.named
+            .get(key.name)
+            .and_then(|m| m.value(key))
+            .and_then(|m| m.as_any().downcast_ref::<T>())
+    }
+
+    pub fn get_key_mut<T: 'static>(&mut self, key: &MetaKey<T>) -> Option<&mut T> {
+        self.named.get_mut(key.name).and_then(|m| m.value_mut(key))
+    }
+
+    pub fn remove_key<T: 'static>(&mut self, key: &MetaKey<T>) -> Option<T> {
+        self.named.remove(key.name)?.into_value(key)
+    }
+
+    pub fn contains_key(&self, name: &str) -> bool {
+        self.named.contains_key(name)
+    }
+
+    /// Names of all named values, in order
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.named.keys().map(|m| &**m)
+    }
+
+    /// Types of all values inserted without a key, in no particular order
+    pub fn types(&self) -> impl Iterator<Item = TypeId> + '_ {
+        self.values.keys().copied()
+    }
 }
 
 impl Clone for Meta {
@@ -74,7 +276,68 @@ impl Clone for Meta {
             .map(|(k, v)| (k.clone(), v.clone_box()))
             .collect::<HashMap<_, _>>();
 
-        Meta { values }
+        Meta {
+            values,
+            named: self.named.clone(),
+        }
+    }
+}
+
+impl core::fmt::Debug for Meta {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Meta")
+            .field("keys", &self.named.keys().collect::<Vec<_>>())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Serializes the named values of keys created with [`MetaKey::serde`] as a map.
+/// Values read back by deserializing are kept as is until accessed with a typed key.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Meta {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::{Error, SerializeMap};
+
+        let mut map = serializer.serialize_map(None)?;
+        for (name, entry) in &self.named {
+            if let Some(raw) = &entry.raw {
+                map.serialize_entry(name, raw)?;
+                continue;
+            }
+
+            let (Some(codec), Some(value)) = (entry.codec, entry.value.get()) else {
+                continue;
+            };
+
+            if let Some(value) = (codec.encode)(value.as_any()) {
+                map.serialize_entry(name, &value.map_err(S::Error::custom)?)?;
+            }
+        }
+        map.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Meta {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let map = BTreeMap::<String, serde_json::Value>::deserialize(deserializer)?;
+
+        let named = map
+            .into_iter()
+            .map(|(name, raw)| {
+                let entry = Entry {
+                    value: OnceCell::new(),
+                    codec: None,
+                    raw: Some(raw),
+                };
+                (Cow::Owned(name), entry)
+            })
+            .collect();
+
+        Ok(Meta {
+            values: HashMap::default(),
+            named,
+        })
     }
 }
 
@@ -111,10 +374,19 @@ impl<B> Package<B> {
         self.name.file_name().unwrap()
     }
 
+    /// Directory containing the package, empty for packages at the root
+    pub fn parent(&self) -> &RelativePath {
+        self.name.parent().unwrap_or(RelativePath::new(""))
+    }
+
     pub fn mime(&self) -> &Mime {
         &self.mime
     }
 
+    pub fn set_mime(&mut self, mime: Mime) {
+        self.mime = mime;
+    }
+
     pub fn content(&self) -> &B {
         &self.content
     }
@@ -199,6 +471,16 @@ where
     }
 }
 
+/// Key function for [`SourceExt::distinct_by`](pipes::SourceExt::distinct_by) identifying packages by path
+pub fn by_path<B>(package: &Package<B>) -> RelativePathBuf {
+    package.path().to_relative_path_buf()
+}
+
+/// Key function for [`SourceExt::group_by`](pipes::SourceExt::group_by) grouping packages by directory
+pub fn by_parent<B>(package: &Package<B>) -> RelativePathBuf {
+    package.parent().to_relative_path_buf()
+}
+
 pub trait IntoPackage<B> {
     type Future: Future<Output = Result<Package<B>, Error>>;
 
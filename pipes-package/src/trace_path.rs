@@ -0,0 +1,60 @@
+use futures::ready;
+use pin_project_lite::pin_project;
+use pipes::{Error, Work};
+use std::task::Poll;
+
+use crate::Package;
+
+#[derive(Debug, Clone, Copy)]
+pub struct TracePath<T> {
+    pub(crate) worker: T,
+}
+
+impl<T, C, B> Work<C, Package<B>> for TracePath<T>
+where
+    T: Work<C, Package<B>>,
+{
+    type Output = T::Output;
+
+    type Future<'a>
+        = TracePathFuture<T::Future<'a>>
+    where
+        Self: 'a;
+
+    fn call<'a>(&'a self, ctx: C, package: Package<B>) -> Self::Future<'a> {
+        TracePathFuture {
+            path: Some(package.path().to_string()),
+            future: self.worker.call(ctx, package),
+        }
+    }
+}
+
+pin_project! {
+    pub struct TracePathFuture<T> {
+        #[pin]
+        future: T,
+        path: Option<String>,
+    }
+}
+
+impl<T, O> Future for TracePathFuture<T>
+where
+    T: Future<Output = Result<O, Error>>,
+{
+    type Output = Result<O, Error>;
+
+    fn poll(
+        self: core::pin::Pin<&mut Self>,
+        cx: &mut core::task::Context<'_>,
+    ) -> Poll<Self::Output> {
+        let this = self.project();
+        match ready!(this.future.poll(cx)) {
+            Ok(ret) => Poll::Ready(Ok(ret)),
+            Err(err) if err.path().is_some() => Poll::Ready(Err(err)),
+            Err(err) => {
+                let path = this.path.take().expect("poll after done");
+                Poll::Ready(Err(err.with_path(path)))
+            }
+        }
+    }
+}
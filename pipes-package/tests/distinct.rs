@@ -0,0 +1,56 @@
+use futures::StreamExt;
+use pipes::{ConflictPolicy, Error, Source};
+use pipes_package::{Bytes, Package, mime, prelude::SourceExt};
+
+fn source(files: &[(&str, &'static str)]) -> Vec<Result<Package<Bytes>, Error>> {
+    files
+        .iter()
+        .map(|(path, content)| {
+            Ok(Package::new(
+                *path,
+                mime::TEXT_PLAIN,
+                Bytes::from_static(content.as_bytes()),
+            ))
+        })
+        .collect()
+}
+
+async fn distinct(policy: ConflictPolicy) -> Vec<(String, Bytes)> {
+    let theme = source(&[("index.html", "theme"), ("style.css", "theme")]);
+    let site = source(&[("index.html", "site"), ("about.html", "site")]);
+
+    let merged = pipes::SourceExt::<()>::chain(theme, site);
+
+    SourceExt::<(), Bytes>::distinct(merged, policy)
+        .create_stream(())
+        .map(|ret| {
+            let package = ret.unwrap();
+            (package.path().to_string(), package.content().clone())
+        })
+        .collect()
+        .await
+}
+
+#[tokio::test]
+async fn first_source_wins() {
+    assert_eq!(
+        distinct(ConflictPolicy::FirstWins).await,
+        vec![
+            ("index.html".to_string(), Bytes::from("theme")),
+            ("style.css".to_string(), Bytes::from("theme")),
+            ("about.html".to_string(), Bytes::from("site")),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn last_source_wins() {
+    assert_eq!(
+        distinct(ConflictPolicy::LastWins).await,
+        vec![
+            ("index.html".to_string(), Bytes::from("site")),
+            ("style.css".to_string(), Bytes::from("theme")),
+            ("about.html".to_string(), Bytes::from("site")),
+        ]
+    );
+}
@@ -0,0 +1,59 @@
+#![cfg(feature = "serde")]
+
+use pipes_package::{Meta, MetaKey};
+
+const TITLE: MetaKey<String> = MetaKey::serde("title");
+const DRAFT: MetaKey<String> = MetaKey::new("draft");
+
+#[test]
+fn named_keys_do_not_collide() {
+    let mut meta = Meta::default();
+    meta.insert_key(&TITLE, "Title".to_string());
+    meta.insert_key(&DRAFT, "yes".to_string());
+
+    assert_eq!(meta.get_key(&TITLE).map(String::as_str), Some("Title"));
+    assert_eq!(meta.get_key(&DRAFT).map(String::as_str), Some("yes"));
+    assert_eq!(meta.keys().collect::<Vec<_>>(), vec!["draft", "title"]);
+}
+
+#[test]
+fn serde_keys_round_trip() {
+    let mut meta = Meta::default();
+    meta.insert_key(&TITLE, "Title".to_string());
+    meta.insert_key(&DRAFT, "yes".to_string());
+
+    let value = serde_json::to_value(&meta).unwrap();
+    assert_eq!(value, serde_json::json!({ "title": "Title" }));
+
+    let mut meta: Meta = serde_json::from_value(value).unwrap();
+    assert_eq!(meta.get_key(&TITLE).map(String::as_str), Some("Title"));
+
+    let mut decoded: Meta = serde_json::from_value(serde_json::to_value(&meta).unwrap()).unwrap();
+    assert_eq!(
+        decoded.insert_key(&TITLE, "Other".to_string()).as_deref(),
+        Some("Title")
+    );
+    assert_eq!(meta.remove_key(&TITLE).as_deref(), Some("Title"));
+}
+
+#[test]
+fn mismatched_key_type_keeps_serialized_value() {
+    const WRONG: MetaKey<u32> = MetaKey::serde("title");
+
+    let mut meta = Meta::default();
+    meta.insert_key(&TITLE, "Title".to_string());
+
+    let mut meta: Meta = serde_json::from_value(serde_json::to_value(&meta).unwrap()).unwrap();
+    assert!(meta.get_key_mut(&WRONG).is_none());
+    assert_eq!(
+        serde_json::to_value(&meta).unwrap(),
+        serde_json::json!({ "title": "Title" })
+    );
+
+    assert!(meta.get_key_mut(&TITLE).is_some());
+    assert!(meta.get_key_mut(&WRONG).is_none());
+    assert_eq!(
+        serde_json::to_value(&meta).unwrap(),
+        serde_json::json!({ "title": "Title" })
+    );
+}
@@ -0,0 +1,159 @@
+use std::{collections::BTreeMap, ops::Range, sync::Arc};
+
+use futures::future::BoxFuture;
+use pipes::Work;
+use pipes_package::{Bytes, Content, MetaKey, Package};
+use toback::Toback;
+
+/// Front matter parsed without a specific type
+pub type FrontMatterMap = BTreeMap<String, serde_json::Value>;
+
+/// Meta key used by [`front_matter`]
+pub const FRONT_MATTER: MetaKey<FrontMatterMap> = MetaKey::serde("front_matter");
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Yaml,
+    Toml,
+}
+
+impl Format {
+    fn delimiter(self) -> &'static [u8] {
+        match self {
+            Format::Yaml => b"---",
+            Format::Toml => b"+++",
+        }
+    }
+
+    // Only the extension is used to look up the encoder
+    fn path(self) -> &'static str {
+        match self {
+            Format::Yaml => "front_matter.yaml",
+            Format::Toml => "front_matter.toml",
+        }
+    }
+}
+
+/// Strips a leading `---` (yaml) or `+++` (toml) delimited block from the content
+/// and stores it in the package meta. Packages without front matter pass through
+/// unchanged, an empty block is stripped without storing anything.
+pub struct FrontMatter<T>
+where
+    T: serde::de::DeserializeOwned + serde::ser::Serialize,
+{
+    toback: Arc<Toback<T>>,
+    key: MetaKey<T>,
+}
+
+impl<T> Clone for FrontMatter<T>
+where
+    T: serde::de::DeserializeOwned + serde::ser::Serialize,
+{
+    fn clone(&self) -> Self {
+        Self {
+            toback: self.toback.clone(),
+            key: self.key,
+        }
+    }
+}
+
+impl<T> FrontMatter<T>
+where
+    T: serde::de::DeserializeOwned + serde::ser::Serialize,
+{
+    pub fn new(key: MetaKey<T>) -> FrontMatter<T> {
+        FrontMatter {
+            toback: Toback::new().into(),
+            key,
+        }
+    }
+}
+
+impl<C, B, T> Work<C, Package<B>> for FrontMatter<T>
+where
+    T: serde::de::DeserializeOwned + serde::ser::Serialize + Clone + Send + 'static,
+    B: Content + Send + 'static,
+{
+    type Output = Package<Bytes>;
+
+    type Future<'a>
+        = BoxFuture<'a, Result<Self::Output, pipes::Error>>
+    where
+        Self: 'a;
+
+    fn call<'a>(&'a self, _ctx: C, mut package: Package<B>) -> Self::Future<'a> {
+        Box::pin(async move {
+            let body = package.content_mut().bytes().await?;
+
+            let Some((format, front, rest)) = split(&body) else {
+                return Ok(package.map_content(body));
+            };
+
+            let front = &body[front];
+            if !front.trim_ascii().is_empty() {
+                let Some(encoder) = self.toback.encoder_from_path(format.path()) else {
+                    return Err(pipes::Error::new(format!(
+                        "Encoder not found for front matter: {}",
+                        format.path()
+                    ))
+                    .with_path(package.path().to_string()));
+                };
+
+                let value = encoder
+                    .load(front)
+                    .map_err(|err| pipes::Error::new(err).with_path(package.path().to_string()))?;
+
+                package.meta_mut().insert_key(&self.key, value);
+            }
+
+            Ok(package.map_content(body.slice(rest..)))
+        })
+    }
+}
+
+/// Parse front matter into a [`FrontMatterMap`] stored under [`FRONT_MATTER`]
+pub fn front_matter() -> FrontMatter<FrontMatterMap> {
+    FrontMatter::new(FRONT_MATTER)
+}
+
+/// Returns the format, the range of the front matter and the offset of the remaining body
+fn split(body: &[u8]) -> Option<(Format, Range<usize>, usize)> {
+    let format = [Format::Yaml, Format::Toml]
+        .into_iter()
+        .find(|format| body.starts_with(format.delimiter()))?;
+
+    let mut lines = Lines { body, offset: 0 };
+
+    let (open, _, start) = lines.next()?;
+    if open != format.delimiter() {
+        return None;
+    }
+
+    lines
+        .find(|(line, _, _)| *line == format.delimiter())
+        .map(|(_, end, rest)| (format, start..end, rest))
+}
+
+/// Lines without trailing whitespace, along with their start and end offsets
+struct Lines<'a> {
+    body: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Iterator for Lines<'a> {
+    type Item = (&'a [u8], usize, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let start = self.offset;
+        let rest = self.body.get(start..).filter(|rest| !rest.is_empty())?;
+
+        let (line, len) = match rest.iter().position(|&b| b == b'\n') {
+            Some(idx) => (&rest[..idx], idx + 1),
+            None => (rest, rest.len()),
+        };
+
+        self.offset += len;
+
+        Some((line.trim_ascii_end(), start, self.offset))
+    }
+}
@@ -2,7 +2,11 @@ mod channel;
 mod parse;
 pub use self::{channel::*, parse::*};
 
+#[cfg(feature = "front-matter")]
+mod front_matter;
 #[cfg(feature = "serde")]
 mod serialize;
+#[cfg(feature = "front-matter")]
+pub use self::front_matter::*;
 #[cfg(feature = "serde")]
 pub use self::serialize::*;
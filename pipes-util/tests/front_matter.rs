@@ -0,0 +1,94 @@
+#![cfg(feature = "front-matter")]
+
+use pipes::Work;
+use pipes_package::{Bytes, MetaKey, Package, mime};
+use pipes_util::{FRONT_MATTER, FrontMatter, front_matter};
+use serde::{Deserialize, Serialize};
+
+async fn run(body: &'static str) -> Package<Bytes> {
+    let package = Package::new("post.md", mime::TEXT_PLAIN, Bytes::from(body));
+    front_matter().call((), package).await.unwrap()
+}
+
+fn body(package: &Package<Bytes>) -> &str {
+    std::str::from_utf8(package.content()).unwrap()
+}
+
+#[tokio::test]
+async fn yaml_block() {
+    let package = run("---\ntitle: Hello\ntags: [a, b]\n---\n# Body\n").await;
+
+    let front = package.meta().get_key(&FRONT_MATTER).unwrap();
+    assert_eq!(front["title"], "Hello");
+    assert_eq!(front["tags"], serde_json::json!(["a", "b"]));
+    assert_eq!(body(&package), "# Body\n");
+}
+
+#[tokio::test]
+async fn toml_block() {
+    let package = run("+++\ntitle = \"Hello\"\ndraft = true\n+++\n# Body\n").await;
+
+    let front = package.meta().get_key(&FRONT_MATTER).unwrap();
+    assert_eq!(front["title"], "Hello");
+    assert_eq!(front["draft"], true);
+    assert_eq!(body(&package), "# Body\n");
+}
+
+#[tokio::test]
+async fn typed_value() {
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct Page {
+        title: String,
+    }
+
+    const PAGE: MetaKey<Page> = MetaKey::new("page");
+
+    let package = Package::new(
+        "post.md",
+        mime::TEXT_PLAIN,
+        Bytes::from("+++\ntitle = \"Hello\"\n+++\nbody"),
+    );
+    let package = FrontMatter::new(PAGE).call((), package).await.unwrap();
+
+    assert_eq!(
+        package.meta().get_key(&PAGE),
+        Some(&Page {
+            title: "Hello".to_string()
+        })
+    );
+    assert_eq!(body(&package), "body");
+}
+
+#[tokio::test]
+async fn crlf_line_endings() {
+    let package = run("---\r\ntitle: Hello\r\n---\r\nbody\r\n").await;
+
+    let front = package.meta().get_key(&FRONT_MATTER).unwrap();
+    assert_eq!(front["title"], "Hello");
+    assert_eq!(body(&package), "body\r\n");
+}
+
+#[tokio::test]
+async fn empty_block_is_stripped() {
+    let package = run("---\n---\nbody").await;
+
+    assert!(package.meta().get_key(&FRONT_MATTER).is_none());
+    assert_eq!(body(&package), "body");
+}
+
+#[tokio::test]
+async fn content_without_front_matter_is_unchanged() {
+    for content in [
+        "# Title\n\nbody\n",
+        // A horizontal rule, not a delimiter
+        "----\ntitle: Hello\n---\nbody\n",
+        // Never closed
+        "---\ntitle: Hello\nbody\n",
+        "",
+    ] {
+        let package = run(content).await;
+
+        assert!(package.meta().get_key(&FRONT_MATTER).is_none());
+        assert_eq!(body(&package), content);
+    }
+}
@@ -58,6 +58,7 @@ where
     T2: Unit<C>,
     C: Clone,
 {
+    type Output = (T1::Output, T2::Output);
     type Future<'a>
         = AndUnitFuture<T1::Future<'a>, T2::Future<'a>>
     where
@@ -134,7 +135,7 @@ where
 }
 
 pin_project! {
-    pub struct AndUnitFuture<T1, T2> where T1: Future<Output = ()>, T2: Future<Output = ()> {
+    pub struct AndUnitFuture<T1, T2> where T1: Future, T2: Future {
         #[pin]
         future: futures::future::Join<T1, T2>
     }
@@ -142,17 +143,16 @@ pin_project! {
 
 impl<T1, T2> Future for AndUnitFuture<T1, T2>
 where
-    T1: Future<Output = ()>,
-    T2: Future<Output = ()>,
+    T1: Future,
+    T2: Future,
 {
-    type Output = ();
+    type Output = (T1::Output, T2::Output);
 
     fn poll(
         self: core::pin::Pin<&mut Self>,
         cx: &mut core::task::Context<'_>,
     ) -> Poll<Self::Output> {
         let this = self.project();
-        ready!(this.future.poll(cx));
-        Poll::Ready(())
+        this.future.poll(cx)
     }
 }
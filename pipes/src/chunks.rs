@@ -0,0 +1,268 @@
+use alloc::vec::Vec;
+use core::{pin::Pin, task::Poll, time::Duration};
+
+use futures::{ready, Future, Stream, TryStream};
+use pin_project_lite::pin_project;
+
+use crate::{timer::Timer, Error, Source};
+
+#[derive(Debug, Clone, Copy)]
+pub struct Chunks<S> {
+    source: S,
+    size: usize,
+}
+
+impl<S> Chunks<S> {
+    /// Batches of at most `size` items. A size of zero is treated as one.
+    pub fn new(source: S, size: usize) -> Chunks<S> {
+        Chunks {
+            source,
+            size: size.max(1),
+        }
+    }
+}
+
+impl<S, C> Source<C> for Chunks<S>
+where
+    S: Source<C>,
+{
+    type Item = Vec<S::Item>;
+
+    type Stream<'a>
+        = ChunksStream<S::Stream<'a>, S::Item>
+    where
+        S: 'a;
+
+    fn create_stream<'a>(self, ctx: C) -> Self::Stream<'a> {
+        ChunksStream {
+            stream: self.source.create_stream(ctx),
+            items: Vec::with_capacity(self.size),
+            size: self.size,
+            done: false,
+        }
+    }
+}
+
+pin_project! {
+    pub struct ChunksStream<T, I> {
+        #[pin]
+        stream: T,
+        items: Vec<I>,
+        size: usize,
+        done: bool,
+    }
+}
+
+impl<T, I> Stream for ChunksStream<T, I>
+where
+    T: TryStream<Ok = I, Error = Error>,
+{
+    type Item = Result<Vec<I>, Error>;
+
+    fn poll_next(
+        self: Pin<&mut Self>,
+        cx: &mut core::task::Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+
+        while !*this.done {
+            match ready!(this.stream.as_mut().try_poll_next(cx)) {
+                Some(Ok(item)) => {
+                    this.items.push(item);
+                    if this.items.len() >= *this.size {
+                        return Poll::Ready(Some(Ok(take(this.items, *this.size))));
+                    }
+                }
+                Some(Err(err)) => return Poll::Ready(Some(Err(err))),
+                None => *this.done = true,
+            }
+        }
+
+        if this.items.is_empty() {
+            Poll::Ready(None)
+        } else {
+            Poll::Ready(Some(Ok(core::mem::take(this.items))))
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ChunksTimeout<S, T> {
+    source: S,
+    size: usize,
+    duration: Duration,
+    timer: T,
+}
+
+impl<S, T> ChunksTimeout<S, T> {
+    /// Batches of at most `size` items, emitted early when `duration` has passed since
+    /// the first item of the batch arrived. A size of zero is treated as one.
+    pub fn new(source: S, size: usize, duration: Duration, timer: T) -> ChunksTimeout<S, T> {
+        ChunksTimeout {
+            source,
+            size: size.max(1),
+            duration,
+            timer,
+        }
+    }
+}
+
+impl<S, T, C> Source<C> for ChunksTimeout<S, T>
+where
+    S: Source<C>,
+    T: Timer + 'static,
+{
+    type Item = Vec<S::Item>;
+
+    type Stream<'a>
+        = ChunksTimeoutStream<S::Stream<'a>, S::Item, T>
+    where
+        S: 'a;
+
+    fn create_stream<'a>(self, ctx: C) -> Self::Stream<'a> {
+        ChunksTimeoutStream {
+            stream: self.source.create_stream(ctx),
+            items: Vec::with_capacity(self.size),
+            size: self.size,
+            sleep: None,
+            duration: self.duration,
+            timer: self.timer,
+            done: false,
+        }
+    }
+}
+
+pin_project! {
+    pub struct ChunksTimeoutStream<S, I, T> where T: Timer {
+        #[pin]
+        stream: S,
+        items: Vec<I>,
+        size: usize,
+        #[pin]
+        sleep: Option<T::Sleep>,
+        duration: Duration,
+        timer: T,
+        done: bool,
+    }
+}
+
+impl<S, I, T> Stream for ChunksTimeoutStream<S, I, T>
+where
+    S: TryStream<Ok = I, Error = Error>,
+    T: Timer,
+{
+    type Item = Result<Vec<I>, Error>;
+
+    fn poll_next(
+        self: Pin<&mut Self>,
+        cx: &mut core::task::Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+
+        while !*this.done {
+            match this.stream.as_mut().try_poll_next(cx) {
+                Poll::Ready(Some(Ok(item))) => {
+                    if this.items.is_empty() {
+                        this.sleep.set(Some(this.timer.sleep(*this.duration)));
+                    }
+                    this.items.push(item);
+                    if this.items.len() >= *this.size {
+                        this.sleep.set(None);
+                        return Poll::Ready(Some(Ok(take(this.items, *this.size))));
+                    }
+                }
+                Poll::Ready(Some(Err(err))) => return Poll::Ready(Some(Err(err))),
+                Poll::Ready(None) => *this.done = true,
+                Poll::Pending => {
+                    let Some(sleep) = this.sleep.as_mut().as_pin_mut() else {
+                        return Poll::Pending;
+                    };
+                    ready!(sleep.poll(cx));
+                    this.sleep.set(None);
+                    return Poll::Ready(Some(Ok(take(this.items, *this.size))));
+                }
+            }
+        }
+
+        this.sleep.set(None);
+
+        if this.items.is_empty() {
+            Poll::Ready(None)
+        } else {
+            Poll::Ready(Some(Ok(core::mem::take(this.items))))
+        }
+    }
+}
+
+fn take<I>(items: &mut Vec<I>, size: usize) -> Vec<I> {
+    core::mem::replace(items, Vec::with_capacity(size))
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Unbatch<S> {
+    source: S,
+}
+
+impl<S> Unbatch<S> {
+    pub fn new(source: S) -> Unbatch<S> {
+        Unbatch { source }
+    }
+}
+
+impl<S, C> Source<C> for Unbatch<S>
+where
+    S: Source<C>,
+    S::Item: IntoIterator,
+{
+    type Item = <S::Item as IntoIterator>::Item;
+
+    type Stream<'a>
+        = UnbatchStream<S::Stream<'a>, <S::Item as IntoIterator>::IntoIter>
+    where
+        S: 'a;
+
+    fn create_stream<'a>(self, ctx: C) -> Self::Stream<'a> {
+        UnbatchStream {
+            stream: self.source.create_stream(ctx),
+            current: None,
+        }
+    }
+}
+
+pin_project! {
+    pub struct UnbatchStream<S, I> {
+        #[pin]
+        stream: S,
+        current: Option<I>,
+    }
+}
+
+impl<S, I> Stream for UnbatchStream<S, I>
+where
+    S: TryStream<Error = Error>,
+    S::Ok: IntoIterator<IntoIter = I>,
+    I: Iterator<Item = <S::Ok as IntoIterator>::Item>,
+{
+    type Item = Result<I::Item, Error>;
+
+    fn poll_next(
+        self: Pin<&mut Self>,
+        cx: &mut core::task::Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+
+        loop {
+            if let Some(item) = this.current.as_mut().and_then(Iterator::next) {
+                return Poll::Ready(Some(Ok(item)));
+            }
+
+            *this.current = None;
+
+            match ready!(this.stream.as_mut().try_poll_next(cx)) {
+                Some(Ok(batch)) => *this.current = Some(batch.into_iter()),
+                Some(Err(err)) => return Poll::Ready(Some(Err(err))),
+                None => return Poll::Ready(None),
+            }
+        }
+    }
+}
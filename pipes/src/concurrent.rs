@@ -0,0 +1,164 @@
+use core::{marker::PhantomData, mem::transmute, pin::Pin, task::Poll};
+
+use futures::{
+    future::{ready, Either, Ready},
+    stream::{FuturesOrdered, FuturesUnordered},
+    Future, Stream, StreamExt, TryStream,
+};
+use pin_project_lite::pin_project;
+
+use crate::{error::Error, source::Source, work::Work};
+
+#[derive(Debug)]
+pub struct Concurrent<S, W, C> {
+    source: S,
+    work: W,
+    limit: usize,
+    ordered: bool,
+    ctx: PhantomData<C>,
+}
+
+impl<S: Clone, W: Clone, C> Clone for Concurrent<S, W, C> {
+    fn clone(&self) -> Self {
+        Concurrent {
+            source: self.source.clone(),
+            work: self.work.clone(),
+            limit: self.limit,
+            ordered: self.ordered,
+            ctx: PhantomData,
+        }
+    }
+}
+
+impl<S: Copy, W: Copy, C> Copy for Concurrent<S, W, C> {}
+
+unsafe impl<S: Send, W: Send, C> Send for Concurrent<S, W, C> {}
+
+unsafe impl<S: Sync, W: Sync, C> Sync for Concurrent<S, W, C> {}
+
+impl<S, W, C> Concurrent<S, W, C> {
+    /// Runs `work` on up to `limit` items at a time. A limit of zero is treated as one.
+    /// Output is yielded in source order.
+    pub fn new(source: S, work: W, limit: usize) -> Concurrent<S, W, C> {
+        Concurrent {
+            source,
+            work,
+            limit: limit.max(1),
+            ordered: true,
+            ctx: PhantomData,
+        }
+    }
+
+    /// Yield output as soon as it completes instead of in source order.
+    pub fn unordered(mut self) -> Self {
+        self.ordered = false;
+        self
+    }
+}
+
+impl<S, W, C> Source<C> for Concurrent<S, W, C>
+where
+    S: Source<C> + 'static,
+    W: Work<C, S::Item> + 'static,
+    C: Clone + 'static,
+{
+    type Item = W::Output;
+    type Stream<'a> = ConcurrentStream<'a, S, W, C>;
+
+    fn create_stream<'a>(self, ctx: C) -> Self::Stream<'a> {
+        let futures = if self.ordered {
+            InFlight::Ordered(FuturesOrdered::new())
+        } else {
+            InFlight::Unordered(FuturesUnordered::new())
+        };
+
+        ConcurrentStream {
+            stream: self.source.create_stream(ctx.clone()),
+            futures,
+            work: self.work,
+            limit: self.limit,
+            done: false,
+            ctx,
+        }
+    }
+}
+
+enum InFlight<F: Future> {
+    Ordered(FuturesOrdered<F>),
+    Unordered(FuturesUnordered<F>),
+}
+
+impl<F: Future> InFlight<F> {
+    fn len(&self) -> usize {
+        match self {
+            Self::Ordered(futures) => futures.len(),
+            Self::Unordered(futures) => futures.len(),
+        }
+    }
+
+    fn push(&mut self, future: F) {
+        match self {
+            Self::Ordered(futures) => futures.push_back(future),
+            Self::Unordered(futures) => futures.push(future),
+        }
+    }
+
+    fn poll_next(&mut self, cx: &mut core::task::Context<'_>) -> Poll<Option<F::Output>> {
+        match self {
+            Self::Ordered(futures) => futures.poll_next_unpin(cx),
+            Self::Unordered(futures) => futures.poll_next_unpin(cx),
+        }
+    }
+}
+
+pin_project! {
+    #[project(!Unpin)]
+    pub struct ConcurrentStream<'a, T, W, C> where W: Work<C, T::Item>, W: 'static, T: Source<C>, T: 'static {
+        #[pin]
+        stream: T::Stream<'a>,
+        // Declared before `work` so in-flight futures are dropped before the work they borrow.
+        // Source errors are queued with the futures so they keep their place in ordered mode
+        futures: InFlight<Either<W::Future<'a>, Ready<Result<W::Output, Error>>>>,
+        work: W,
+        limit: usize,
+        done: bool,
+        ctx: C
+    }
+}
+
+impl<'a, T: 'static, W: 'static, C> Stream for ConcurrentStream<'a, T, W, C>
+where
+    W: Work<C, T::Item>,
+    T: Source<C>,
+    C: Clone,
+    Self: 'a,
+{
+    type Item = Result<W::Output, Error>;
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut core::task::Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        let mut this = self.as_mut().project();
+
+        while !*this.done && this.futures.len() < *this.limit {
+            match this.stream.as_mut().try_poll_next(cx) {
+                Poll::Ready(Some(Ok(item))) => {
+                    this.futures.push(Either::Left(unsafe {
+                        transmute::<W::Future<'_>, W::Future<'a>>(
+                            this.work.call(this.ctx.clone(), item),
+                        )
+                    }));
+                }
+                Poll::Ready(Some(Err(err))) => this.futures.push(Either::Right(ready(Err(err)))),
+                Poll::Ready(None) => *this.done = true,
+                Poll::Pending => break,
+            }
+        }
+
+        match this.futures.poll_next(cx) {
+            Poll::Ready(Some(ret)) => Poll::Ready(Some(ret)),
+            Poll::Ready(None) if *this.done => Poll::Ready(None),
+            _ => Poll::Pending,
+        }
+    }
+}
@@ -0,0 +1,161 @@
+use alloc::{
+    collections::{btree_map::Entry, BTreeMap},
+    string::String,
+    vec,
+    vec::Vec,
+};
+use core::{fmt, pin::Pin, task::Poll};
+
+use futures::{ready, Stream, TryStream};
+use pin_project_lite::pin_project;
+
+use crate::{Error, Source};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ConflictPolicy {
+    /// Keep the first item with a given key and drop the rest
+    #[default]
+    FirstWins,
+    /// Keep the last item with a given key. Items are held back until the source ends.
+    LastWins,
+    /// Yield a [`DuplicateKey`] error for every repeated key
+    Error,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DuplicateKey {
+    key: String,
+}
+
+impl DuplicateKey {
+    /// Debug representation of the duplicated key
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+}
+
+impl fmt::Display for DuplicateKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "duplicate key: {}", self.key)
+    }
+}
+
+impl core::error::Error for DuplicateKey {}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Distinct<S, F> {
+    source: S,
+    func: F,
+    policy: ConflictPolicy,
+}
+
+impl<S, F> Distinct<S, F> {
+    pub fn new(source: S, func: F, policy: ConflictPolicy) -> Distinct<S, F> {
+        Distinct {
+            source,
+            func,
+            policy,
+        }
+    }
+}
+
+impl<S, F, K, C> Source<C> for Distinct<S, F>
+where
+    S: Source<C>,
+    S::Item: 'static,
+    F: Fn(&S::Item) -> K + 'static,
+    K: Ord + fmt::Debug + 'static,
+{
+    type Item = S::Item;
+
+    type Stream<'a>
+        = DistinctStream<S::Stream<'a>, S::Item, F, K>
+    where
+        S: 'a;
+
+    fn create_stream<'a>(self, ctx: C) -> Self::Stream<'a> {
+        DistinctStream {
+            stream: self.source.create_stream(ctx),
+            seen: BTreeMap::new(),
+            held: Vec::new(),
+            output: None,
+            func: self.func,
+            policy: self.policy,
+        }
+    }
+}
+
+pin_project! {
+    pub struct DistinctStream<S, I, F, K> {
+        #[pin]
+        stream: S,
+        // Key to position in `held`
+        seen: BTreeMap<K, usize>,
+        held: Vec<I>,
+        output: Option<vec::IntoIter<I>>,
+        func: F,
+        policy: ConflictPolicy,
+    }
+}
+
+impl<S, I, F, K> Stream for DistinctStream<S, I, F, K>
+where
+    S: TryStream<Ok = I, Error = Error>,
+    F: Fn(&I) -> K,
+    K: Ord + fmt::Debug,
+{
+    type Item = Result<I, Error>;
+
+    fn poll_next(
+        self: Pin<&mut Self>,
+        cx: &mut core::task::Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+
+        loop {
+            if let Some(output) = this.output {
+                return Poll::Ready(output.next().map(Ok));
+            }
+
+            let item = match ready!(this.stream.as_mut().try_poll_next(cx)) {
+                Some(Ok(item)) => item,
+                Some(Err(err)) => return Poll::Ready(Some(Err(err))),
+                None if *this.policy == ConflictPolicy::LastWins => {
+                    *this.output = Some(core::mem::take(this.held).into_iter());
+                    continue;
+                }
+                None => return Poll::Ready(None),
+            };
+
+            let key = (this.func)(&item);
+
+            match this.policy {
+                ConflictPolicy::FirstWins => {
+                    if let Entry::Vacant(entry) = this.seen.entry(key) {
+                        entry.insert(0);
+                        return Poll::Ready(Some(Ok(item)));
+                    }
+                }
+                ConflictPolicy::Error => match this.seen.entry(key) {
+                    Entry::Vacant(entry) => {
+                        entry.insert(0);
+                        return Poll::Ready(Some(Ok(item)));
+                    }
+                    Entry::Occupied(entry) => {
+                        return Poll::Ready(Some(Err(Error::new(DuplicateKey {
+                            key: alloc::format!("{:?}", entry.key()),
+                        }))));
+                    }
+                },
+                ConflictPolicy::LastWins => {
+                    if let Some(idx) = this.seen.get(&key) {
+                        this.held[*idx] = item;
+                    } else {
+                        this.seen.insert(key, this.held.len());
+                        this.held.push(item);
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,6 +1,8 @@
-use alloc::boxed::Box;
+use alloc::{borrow::Cow, boxed::Box, vec::Vec};
 use core::fmt;
 
+use crate::timeout::TimeoutError;
+
 pub type BoxError = Box<dyn core::error::Error + Send + Sync>;
 
 pub type Result<T> = core::result::Result<T, Error>;
@@ -8,22 +10,84 @@ pub type Result<T> = core::result::Result<T, Error>;
 #[derive(Debug)]
 pub struct Error {
     inner: BoxError,
+    stage: Option<Cow<'static, str>>,
+    path: Option<Cow<'static, str>>,
+    context: Vec<Cow<'static, str>>,
 }
 
 impl Error {
     pub fn new<T: Into<BoxError>>(error: T) -> Error {
         Error {
             inner: error.into(),
+            stage: None,
+            path: None,
+            context: Vec::new(),
         }
     }
 
     pub fn inner(&self) -> &BoxError {
         &self.inner
     }
+
+    pub fn is<T: core::error::Error + 'static>(&self) -> bool {
+        self.inner.is::<T>()
+    }
+
+    pub fn downcast_ref<T: core::error::Error + 'static>(&self) -> Option<&T> {
+        self.inner.downcast_ref()
+    }
+
+    /// Whether the error was produced by [`WorkExt::timeout_with`](crate::WorkExt::timeout_with)
+    pub fn is_timeout(&self) -> bool {
+        self.is::<TimeoutError>()
+    }
+
+    /// Name of the pipeline stage the error occurred in, see [`WorkExt::named`](crate::WorkExt::named)
+    pub fn stage(&self) -> Option<&str> {
+        self.stage.as_deref()
+    }
+
+    pub fn with_stage(mut self, stage: impl Into<Cow<'static, str>>) -> Error {
+        self.stage = Some(stage.into());
+        self
+    }
+
+    /// Path of the package being processed when the error occurred
+    pub fn path(&self) -> Option<&str> {
+        self.path.as_deref()
+    }
+
+    pub fn with_path(mut self, path: impl Into<Cow<'static, str>>) -> Error {
+        self.path = Some(path.into());
+        self
+    }
+
+    /// Add a frame describing what was being done when the error occurred
+    pub fn context(mut self, context: impl Into<Cow<'static, str>>) -> Error {
+        self.context.push(context.into());
+        self
+    }
+
+    /// Context frames, outermost first
+    pub fn frames(&self) -> impl Iterator<Item = &str> {
+        self.context.iter().rev().map(|m| m.as_ref())
+    }
 }
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(stage) = &self.stage {
+            write!(f, "[{stage}] ")?;
+        }
+
+        if let Some(path) = &self.path {
+            write!(f, "{path}: ")?;
+        }
+
+        for frame in self.frames() {
+            write!(f, "{frame}: ")?;
+        }
+
         write!(f, "{}", self.inner)
     }
 }
@@ -33,3 +97,26 @@ impl core::error::Error for Error {
         self.inner.source()
     }
 }
+
+pub trait ResultExt<T> {
+    fn context(self, context: impl Into<Cow<'static, str>>) -> Result<T>;
+
+    fn with_context<F, U>(self, func: F) -> Result<T>
+    where
+        F: FnOnce() -> U,
+        U: Into<Cow<'static, str>>;
+}
+
+impl<T> ResultExt<T> for Result<T> {
+    fn context(self, context: impl Into<Cow<'static, str>>) -> Result<T> {
+        self.map_err(|err| err.context(context))
+    }
+
+    fn with_context<F, U>(self, func: F) -> Result<T>
+    where
+        F: FnOnce() -> U,
+        U: Into<Cow<'static, str>>,
+    {
+        self.map_err(|err| err.context(func()))
+    }
+}
@@ -0,0 +1,175 @@
+use alloc::{boxed::Box, vec::Vec};
+use async_stream::try_stream;
+use futures::{future::BoxFuture, stream::BoxStream, TryStreamExt};
+
+use crate::{cloned::AsyncClone, Error, Source, Work};
+
+/// A fixed set of works that all accept the same input and produce the same output.
+pub trait Branches<C, T> {
+    type Output;
+
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn call<'a>(
+        &'a self,
+        index: usize,
+        ctx: C,
+        package: T,
+    ) -> BoxFuture<'a, Result<Self::Output, Error>>
+    where
+        C: 'a,
+        T: 'a;
+}
+
+impl<W, C, T> Branches<C, T> for Vec<W>
+where
+    W: Work<C, T>,
+    for<'a> W::Future<'a>: Send,
+{
+    type Output = W::Output;
+
+    fn len(&self) -> usize {
+        Vec::len(self)
+    }
+
+    fn call<'a>(
+        &'a self,
+        index: usize,
+        ctx: C,
+        package: T,
+    ) -> BoxFuture<'a, Result<Self::Output, Error>>
+    where
+        C: 'a,
+        T: 'a,
+    {
+        Box::pin(self[index].call(ctx, package))
+    }
+}
+
+macro_rules! branches {
+    ($len: literal, $first: ident => $first_index: tt $(, $type: ident => $index: tt)*) => {
+        impl<$first, $($type,)* C, T> Branches<C, T> for ($first, $($type,)*)
+        where
+            $first: Work<C, T>,
+            for<'a> $first::Future<'a>: Send,
+            $(
+                $type: Work<C, T, Output = $first::Output>,
+                for<'a> $type::Future<'a>: Send,
+            )*
+        {
+            type Output = $first::Output;
+
+            fn len(&self) -> usize {
+                $len
+            }
+
+            fn call<'a>(
+                &'a self,
+                index: usize,
+                ctx: C,
+                package: T,
+            ) -> BoxFuture<'a, Result<Self::Output, Error>>
+            where
+                C: 'a,
+                T: 'a,
+            {
+                match index {
+                    $first_index => Box::pin(self.$first_index.call(ctx, package)),
+                    $(
+                        $index => Box::pin(self.$index.call(ctx, package)),
+                    )*
+                    _ => panic!("branch index out of bounds: {index}"),
+                }
+            }
+        }
+    };
+}
+
+branches!(1, T1 => 0);
+branches!(2, T1 => 0, T2 => 1);
+branches!(3, T1 => 0, T2 => 1, T3 => 2);
+branches!(4, T1 => 0, T2 => 1, T3 => 2, T4 => 3);
+branches!(5, T1 => 0, T2 => 1, T3 => 2, T4 => 3, T5 => 4);
+branches!(6, T1 => 0, T2 => 1, T3 => 2, T4 => 3, T5 => 4, T6 => 5);
+branches!(7, T1 => 0, T2 => 1, T3 => 2, T4 => 3, T5 => 4, T6 => 5, T7 => 6);
+branches!(8, T1 => 0, T2 => 1, T3 => 2, T4 => 3, T5 => 4, T6 => 5, T7 => 6, T8 => 7);
+
+#[derive(Debug, Clone, Copy)]
+pub struct FanOut<S, B> {
+    source: S,
+    branches: B,
+    concurrent: bool,
+}
+
+impl<S, B> FanOut<S, B> {
+    pub fn new(source: S, branches: B) -> FanOut<S, B> {
+        FanOut {
+            source,
+            branches,
+            concurrent: false,
+        }
+    }
+
+    /// Run all branches for an item at the same time instead of one after another.
+    /// Output is still yielded in branch order.
+    pub fn concurrent(mut self) -> Self {
+        self.concurrent = true;
+        self
+    }
+}
+
+impl<S, B, C> Source<C> for FanOut<S, B>
+where
+    S: Source<C> + 'static + Send,
+    for<'a> S::Stream<'a>: Send,
+    S::Item: AsyncClone + Send,
+    for<'a> <S::Item as AsyncClone>::Future<'a>: Send,
+    B: Branches<C, S::Item> + 'static + Send,
+    B::Output: Send,
+    for<'a> C: Send + 'a,
+    C: Clone,
+{
+    type Item = B::Output;
+
+    type Stream<'a> = BoxStream<'a, Result<B::Output, Error>>;
+
+    fn create_stream<'a>(self, ctx: C) -> Self::Stream<'a> {
+        Box::pin(try_stream! {
+            let stream = self.source.create_stream(ctx.clone());
+            futures::pin_mut!(stream);
+
+            // With no branches every item is consumed without output
+            let last = self.branches.len().checked_sub(1);
+
+            while let Some(mut item) = stream.try_next().await? {
+                let Some(last) = last else {
+                    continue;
+                };
+
+                if self.concurrent {
+                    let mut futures = Vec::with_capacity(last + 1);
+                    for index in 0..last {
+                        let clone = item.async_clone().await?;
+                        futures.push(self.branches.call(index, ctx.clone(), clone));
+                    }
+                    futures.push(self.branches.call(last, ctx.clone(), item));
+
+                    for ret in futures::future::join_all(futures).await {
+                        yield ret?;
+                    }
+                } else {
+                    for index in 0..last {
+                        let clone = item.async_clone().await?;
+                        yield self.branches.call(index, ctx.clone(), clone).await?;
+                    }
+
+                    yield self.branches.call(last, ctx.clone(), item).await?;
+                }
+            }
+        })
+    }
+}
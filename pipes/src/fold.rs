@@ -0,0 +1,105 @@
+use core::{pin::Pin, task::Poll};
+
+use futures::{ready, Stream, TryFuture, TryStream};
+use pin_project_lite::pin_project;
+
+use crate::{Error, Source};
+
+#[derive(Debug, Clone, Copy)]
+pub struct Fold<S, A, F> {
+    source: S,
+    init: A,
+    func: F,
+}
+
+impl<S, A, F> Fold<S, A, F> {
+    pub fn new(source: S, init: A, func: F) -> Fold<S, A, F> {
+        Fold { source, init, func }
+    }
+}
+
+impl<S, A, F, U, C> Source<C> for Fold<S, A, F>
+where
+    S: Source<C>,
+    A: 'static,
+    F: Fn(C, A, S::Item) -> U + 'static,
+    U: TryFuture<Ok = A>,
+    U::Error: Into<Error>,
+    C: Clone,
+{
+    type Item = A;
+
+    type Stream<'a>
+        = FoldStream<S::Stream<'a>, A, F, U, C>
+    where
+        S: 'a;
+
+    fn create_stream<'a>(self, ctx: C) -> Self::Stream<'a> {
+        FoldStream {
+            stream: self.source.create_stream(ctx.clone()),
+            acc: Some(self.init),
+            future: None,
+            func: self.func,
+            ctx,
+        }
+    }
+}
+
+pin_project! {
+    pub struct FoldStream<S, A, F, U, C> {
+        #[pin]
+        stream: S,
+        acc: Option<A>,
+        #[pin]
+        future: Option<U>,
+        func: F,
+        ctx: C,
+    }
+}
+
+impl<S, A, F, U, C> Stream for FoldStream<S, A, F, U, C>
+where
+    S: TryStream<Error = Error>,
+    F: Fn(C, A, S::Ok) -> U,
+    U: TryFuture<Ok = A>,
+    U::Error: Into<Error>,
+    C: Clone,
+{
+    type Item = Result<A, Error>;
+
+    fn poll_next(
+        self: Pin<&mut Self>,
+        cx: &mut core::task::Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+
+        loop {
+            if let Some(future) = this.future.as_mut().as_pin_mut() {
+                let ret = ready!(future.try_poll(cx));
+                this.future.set(None);
+                match ret {
+                    Ok(acc) => *this.acc = Some(acc),
+                    Err(err) => return Poll::Ready(Some(Err(err.into()))),
+                }
+            }
+
+            // The accumulator is gone once it has been emitted or an error occurred
+            let Some(acc) = this.acc.take() else {
+                return Poll::Ready(None);
+            };
+
+            match this.stream.as_mut().try_poll_next(cx) {
+                Poll::Ready(Some(Ok(item))) => {
+                    this.future
+                        .set(Some((this.func)(this.ctx.clone(), acc, item)));
+                }
+                Poll::Ready(Some(Err(err))) => return Poll::Ready(Some(Err(err))),
+                Poll::Ready(None) => return Poll::Ready(Some(Ok(acc))),
+                Poll::Pending => {
+                    *this.acc = Some(acc);
+                    return Poll::Pending;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,87 @@
+use alloc::{
+    collections::{btree_map, BTreeMap},
+    vec::Vec,
+};
+use core::{pin::Pin, task::Poll};
+
+use futures::{ready, Stream, TryStream};
+use pin_project_lite::pin_project;
+
+use crate::{Error, Source};
+
+#[derive(Debug, Clone, Copy)]
+pub struct GroupBy<S, F> {
+    source: S,
+    func: F,
+}
+
+impl<S, F> GroupBy<S, F> {
+    pub fn new(source: S, func: F) -> GroupBy<S, F> {
+        GroupBy { source, func }
+    }
+}
+
+impl<S, F, K, C> Source<C> for GroupBy<S, F>
+where
+    S: Source<C>,
+    S::Item: 'static,
+    F: Fn(&S::Item) -> K + 'static,
+    K: Ord + 'static,
+{
+    type Item = (K, Vec<S::Item>);
+
+    type Stream<'a>
+        = GroupByStream<S::Stream<'a>, S::Item, F, K>
+    where
+        S: 'a;
+
+    fn create_stream<'a>(self, ctx: C) -> Self::Stream<'a> {
+        GroupByStream {
+            stream: self.source.create_stream(ctx),
+            groups: BTreeMap::new(),
+            output: None,
+            func: self.func,
+        }
+    }
+}
+
+pin_project! {
+    pub struct GroupByStream<S, I, F, K> {
+        #[pin]
+        stream: S,
+        groups: BTreeMap<K, Vec<I>>,
+        output: Option<btree_map::IntoIter<K, Vec<I>>>,
+        func: F,
+    }
+}
+
+impl<S, I, F, K> Stream for GroupByStream<S, I, F, K>
+where
+    S: TryStream<Ok = I, Error = Error>,
+    F: Fn(&I) -> K,
+    K: Ord,
+{
+    type Item = Result<(K, Vec<I>), Error>;
+
+    fn poll_next(
+        self: Pin<&mut Self>,
+        cx: &mut core::task::Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+
+        loop {
+            if let Some(output) = this.output {
+                return Poll::Ready(output.next().map(Ok));
+            }
+
+            match ready!(this.stream.as_mut().try_poll_next(cx)) {
+                Some(Ok(item)) => {
+                    let key = (this.func)(&item);
+                    this.groups.entry(key).or_default().push(item);
+                }
+                Some(Err(err)) => return Poll::Ready(Some(Err(err))),
+                None => *this.output = Some(core::mem::take(this.groups).into_iter()),
+            }
+        }
+    }
+}
@@ -6,13 +6,27 @@ extern crate alloc;
 extern crate std;
 
 mod and;
+mod chunks;
 mod cloned;
+mod concurrent;
+mod distinct;
 mod error;
+mod fan_out;
+mod fold;
+mod group_by;
 mod matcher;
+mod merge;
+mod named;
 mod pipeline;
+mod retry;
+mod route;
 mod source;
+#[cfg(feature = "tokio")]
+mod spawn;
 mod split;
 mod then;
+mod timeout;
+mod timer;
 mod unit;
 mod when;
 mod work;
@@ -20,12 +34,16 @@ mod work;
 mod wrap;
 
 pub use self::{
-    cloned::*, error::Result, error::*, matcher::*, pipeline::Pipeline, source::*, then::*,
-    unit::*, when::*, work::*,
+    chunks::*, cloned::*, concurrent::*, distinct::*, error::Result, error::*, fan_out::*, fold::*,
+    group_by::*, matcher::*, merge::*, named::*, pipeline::Pipeline, retry::*, route::*, source::*,
+    then::*, timeout::*, timer::*, unit::*, when::*, work::*,
 };
 
+#[cfg(feature = "tokio")]
+pub use self::spawn::{Spawn, SpawnStream};
+
 pub mod prelude {
-    pub use super::{SourceExt, UnitExt, WorkExt};
+    pub use super::{MatcherExt, ResultExt, SourceExt, UnitExt, WorkExt};
 }
 
 pub fn pipe<C, T>(source: T) -> Pipeline<T, NoopWork, C> {
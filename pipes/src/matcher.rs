@@ -1,4 +1,5 @@
-use alloc::{boxed::Box, string::String};
+use alloc::{boxed::Box, string::String, vec::Vec};
+use core::marker::PhantomData;
 
 pub trait Matcher<T: ?Sized>: Send + Sync {
     fn is_match(&self, path: &T) -> bool;
@@ -30,3 +31,144 @@ where
         (self)(path)
     }
 }
+
+pub trait MatcherExt<T: ?Sized>: Matcher<T> {
+    /// Match when both `self` and `other` match
+    fn and<M>(self, other: M) -> MatchAnd<Self, M, T>
+    where
+        Self: Sized,
+        M: Matcher<T>,
+    {
+        MatchAnd {
+            left: self,
+            right: other,
+            _t: PhantomData,
+        }
+    }
+
+    /// Match when either `self` or `other` matches
+    fn or<M>(self, other: M) -> MatchOr<Self, M, T>
+    where
+        Self: Sized,
+        M: Matcher<T>,
+    {
+        MatchOr {
+            left: self,
+            right: other,
+            _t: PhantomData,
+        }
+    }
+
+    /// Match when `self` does not
+    fn not(self) -> MatchNot<Self, T>
+    where
+        Self: Sized,
+    {
+        MatchNot {
+            matcher: self,
+            _t: PhantomData,
+        }
+    }
+}
+
+impl<M, T: ?Sized> MatcherExt<T> for M where M: Matcher<T> {}
+
+// The matched type is part of the combinator types so that matchers generic over it,
+// like globs, can be combined before it is known.
+
+#[derive(Debug, Clone, Copy)]
+pub struct MatchAnd<L, R, T: ?Sized> {
+    left: L,
+    right: R,
+    _t: PhantomData<fn(&T)>,
+}
+
+impl<L, R, T: ?Sized> Matcher<T> for MatchAnd<L, R, T>
+where
+    L: Matcher<T>,
+    R: Matcher<T>,
+{
+    fn is_match(&self, path: &T) -> bool {
+        self.left.is_match(path) && self.right.is_match(path)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct MatchOr<L, R, T: ?Sized> {
+    left: L,
+    right: R,
+    _t: PhantomData<fn(&T)>,
+}
+
+impl<L, R, T: ?Sized> Matcher<T> for MatchOr<L, R, T>
+where
+    L: Matcher<T>,
+    R: Matcher<T>,
+{
+    fn is_match(&self, path: &T) -> bool {
+        self.left.is_match(path) || self.right.is_match(path)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct MatchNot<M, T: ?Sized> {
+    matcher: M,
+    _t: PhantomData<fn(&T)>,
+}
+
+impl<M, T: ?Sized> Matcher<T> for MatchNot<M, T>
+where
+    M: Matcher<T>,
+{
+    fn is_match(&self, path: &T) -> bool {
+        !self.matcher.is_match(path)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AnyOf<M> {
+    matchers: Vec<M>,
+}
+
+impl<M, T: ?Sized> Matcher<T> for AnyOf<M>
+where
+    M: Matcher<T>,
+{
+    fn is_match(&self, path: &T) -> bool {
+        self.matchers.iter().any(|m| m.is_match(path))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AllOf<M> {
+    matchers: Vec<M>,
+}
+
+impl<M, T: ?Sized> Matcher<T> for AllOf<M>
+where
+    M: Matcher<T>,
+{
+    fn is_match(&self, path: &T) -> bool {
+        self.matchers.iter().all(|m| m.is_match(path))
+    }
+}
+
+/// Match when any of `matchers` matches. An empty set never matches.
+pub fn any_of<I>(matchers: I) -> AnyOf<I::Item>
+where
+    I: IntoIterator,
+{
+    AnyOf {
+        matchers: matchers.into_iter().collect(),
+    }
+}
+
+/// Match when all of `matchers` match. An empty set always matches.
+pub fn all_of<I>(matchers: I) -> AllOf<I::Item>
+where
+    I: IntoIterator,
+{
+    AllOf {
+        matchers: matchers.into_iter().collect(),
+    }
+}
@@ -0,0 +1,220 @@
+use core::{pin::Pin, task::Poll};
+
+use futures::{
+    stream::{self, PollNext, SelectWithStrategy},
+    Stream, StreamExt, TryStream,
+};
+use pin_project_lite::pin_project;
+
+use crate::{Error, Source};
+
+#[derive(Debug, Clone, Copy)]
+pub struct Chain<T1, T2> {
+    left: T1,
+    right: T2,
+}
+
+impl<T1, T2> Chain<T1, T2> {
+    pub fn new(left: T1, right: T2) -> Chain<T1, T2> {
+        Chain { left, right }
+    }
+}
+
+impl<T1, T2, C> Source<C> for Chain<T1, T2>
+where
+    T1: Source<C>,
+    T2: Source<C, Item = T1::Item>,
+    C: Clone,
+{
+    type Item = T1::Item;
+    type Stream<'a>
+        = stream::Chain<T1::Stream<'a>, T2::Stream<'a>>
+    where
+        T1: 'a,
+        T2: 'a;
+
+    fn create_stream<'a>(self, ctx: C) -> Self::Stream<'a> {
+        self.left
+            .create_stream(ctx.clone())
+            .chain(self.right.create_stream(ctx))
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Priority<T1, T2> {
+    left: T1,
+    right: T2,
+}
+
+impl<T1, T2> Priority<T1, T2> {
+    pub fn new(left: T1, right: T2) -> Priority<T1, T2> {
+        Priority { left, right }
+    }
+}
+
+fn prefer_left(_: &mut ()) -> PollNext {
+    PollNext::Left
+}
+
+impl<T1, T2, C> Source<C> for Priority<T1, T2>
+where
+    T1: Source<C>,
+    T2: Source<C, Item = T1::Item>,
+    C: Clone,
+{
+    type Item = T1::Item;
+    type Stream<'a>
+        = SelectWithStrategy<T1::Stream<'a>, T2::Stream<'a>, fn(&mut ()) -> PollNext, ()>
+    where
+        T1: 'a,
+        T2: 'a;
+
+    fn create_stream<'a>(self, ctx: C) -> Self::Stream<'a> {
+        stream::select_with_strategy(
+            self.left.create_stream(ctx.clone()),
+            self.right.create_stream(ctx),
+            prefer_left as fn(&mut ()) -> PollNext,
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct MergeSortedBy<T1, T2, F> {
+    left: T1,
+    right: T2,
+    func: F,
+}
+
+impl<T1, T2, F> MergeSortedBy<T1, T2, F> {
+    pub fn new(left: T1, right: T2, func: F) -> MergeSortedBy<T1, T2, F> {
+        MergeSortedBy { left, right, func }
+    }
+}
+
+impl<T1, T2, F, K, C> Source<C> for MergeSortedBy<T1, T2, F>
+where
+    T1: Source<C>,
+    T1::Item: 'static,
+    T2: Source<C, Item = T1::Item>,
+    F: Fn(&T1::Item) -> K + 'static,
+    K: Ord,
+    C: Clone,
+{
+    type Item = T1::Item;
+    type Stream<'a>
+        = MergeSortedStream<T1::Stream<'a>, T2::Stream<'a>, T1::Item, F>
+    where
+        T1: 'a,
+        T2: 'a;
+
+    fn create_stream<'a>(self, ctx: C) -> Self::Stream<'a> {
+        MergeSortedStream {
+            left: Side::new(self.left.create_stream(ctx.clone())),
+            right: Side::new(self.right.create_stream(ctx)),
+            func: self.func,
+        }
+    }
+}
+
+pin_project! {
+    struct Side<S, I> {
+        #[pin]
+        stream: S,
+        head: Option<I>,
+        // Error taken from the stream but not yet reported
+        error: Option<Error>,
+        done: bool,
+    }
+}
+
+impl<S, I> Side<S, I>
+where
+    S: TryStream<Ok = I, Error = Error>,
+{
+    fn new(stream: S) -> Side<S, I> {
+        Side {
+            stream,
+            head: None,
+            error: None,
+            done: false,
+        }
+    }
+
+    /// Ready once the side has a head item or is exhausted
+    fn poll_head(
+        self: Pin<&mut Self>,
+        cx: &mut core::task::Context<'_>,
+    ) -> Poll<Result<(), Error>> {
+        let this = self.project();
+        if let Some(err) = this.error.take() {
+            return Poll::Ready(Err(err));
+        }
+
+        if this.head.is_some() || *this.done {
+            return Poll::Ready(Ok(()));
+        }
+
+        match this.stream.try_poll_next(cx) {
+            Poll::Ready(Some(Ok(item))) => *this.head = Some(item),
+            Poll::Ready(Some(Err(err))) => return Poll::Ready(Err(err)),
+            Poll::Ready(None) => *this.done = true,
+            Poll::Pending => return Poll::Pending,
+        }
+
+        Poll::Ready(Ok(()))
+    }
+}
+
+pin_project! {
+    pub struct MergeSortedStream<S1, S2, I, F> {
+        #[pin]
+        left: Side<S1, I>,
+        #[pin]
+        right: Side<S2, I>,
+        func: F,
+    }
+}
+
+impl<S1, S2, I, F, K> Stream for MergeSortedStream<S1, S2, I, F>
+where
+    S1: TryStream<Ok = I, Error = Error>,
+    S2: TryStream<Ok = I, Error = Error>,
+    F: Fn(&I) -> K,
+    K: Ord,
+{
+    type Item = Result<I, Error>;
+
+    fn poll_next(
+        self: Pin<&mut Self>,
+        cx: &mut core::task::Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+
+        // Both heads are needed to decide, so output only depends on the input order
+        let left = this.left.as_mut().poll_head(cx);
+        let right = this.right.as_mut().poll_head(cx);
+
+        match (left, right) {
+            (Poll::Ready(Err(left)), Poll::Ready(Err(right))) => {
+                *this.right.project().error = Some(right);
+                return Poll::Ready(Some(Err(left)));
+            }
+            (Poll::Ready(Err(err)), _) | (_, Poll::Ready(Err(err))) => {
+                return Poll::Ready(Some(Err(err)))
+            }
+            (Poll::Pending, _) | (_, Poll::Pending) => return Poll::Pending,
+            _ => {}
+        }
+
+        let left = this.left.project().head;
+        let right = this.right.project().head;
+
+        let item = match (left.as_ref(), right.as_ref()) {
+            (Some(l), Some(r)) if (this.func)(r) < (this.func)(l) => right.take(),
+            (Some(_), _) => left.take(),
+            (None, _) => right.take(),
+        };
+
+        Poll::Ready(item.map(Ok))
+    }
+}
@@ -0,0 +1,73 @@
+use alloc::borrow::Cow;
+use core::task::Poll;
+
+use futures::{ready, Future};
+use pin_project_lite::pin_project;
+
+use crate::{Error, Work};
+
+#[derive(Debug, Clone)]
+pub struct Named<W> {
+    work: W,
+    name: Cow<'static, str>,
+}
+
+impl<W> Named<W> {
+    pub fn new(work: W, name: impl Into<Cow<'static, str>>) -> Named<W> {
+        Named {
+            work,
+            name: name.into(),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl<W, C, R> Work<C, R> for Named<W>
+where
+    W: Work<C, R>,
+{
+    type Output = W::Output;
+
+    type Future<'a>
+        = NamedFuture<'a, W::Future<'a>>
+    where
+        Self: 'a;
+
+    fn call<'a>(&'a self, ctx: C, package: R) -> Self::Future<'a> {
+        NamedFuture {
+            future: self.work.call(ctx, package),
+            name: &self.name,
+        }
+    }
+}
+
+pin_project! {
+    pub struct NamedFuture<'a, F> {
+        #[pin]
+        future: F,
+        name: &'a Cow<'static, str>,
+    }
+}
+
+impl<'a, F, T> Future for NamedFuture<'a, F>
+where
+    F: Future<Output = Result<T, Error>>,
+{
+    type Output = Result<T, Error>;
+
+    fn poll(
+        self: core::pin::Pin<&mut Self>,
+        cx: &mut core::task::Context<'_>,
+    ) -> Poll<Self::Output> {
+        let this = self.project();
+        match ready!(this.future.poll(cx)) {
+            Ok(ret) => Poll::Ready(Ok(ret)),
+            // Keep the innermost stage, it is the one that actually failed
+            Err(err) if err.stage().is_some() => Poll::Ready(Err(err)),
+            Err(err) => Poll::Ready(Err(err.with_stage(this.name.clone()))),
+        }
+    }
+}
@@ -3,6 +3,7 @@ use futures::{ready, Stream, TryFuture, TryStream};
 use pin_project_lite::pin_project;
 
 use crate::{
+    concurrent::Concurrent,
     error::Error,
     source::Source,
     work::{NoopWork, Work},
@@ -67,6 +68,10 @@ impl<S, W, C> Pipeline<S, W, C> {
             ctx: PhantomData,
         }
     }
+
+    pub fn concurrent(self, limit: usize) -> Concurrent<S, W, C> {
+        Concurrent::new(self.source, self.work, limit)
+    }
 }
 
 impl<S, W, C> Source<C> for Pipeline<S, W, C>
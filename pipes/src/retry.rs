@@ -0,0 +1,187 @@
+use alloc::{boxed::Box, sync::Arc};
+use core::{
+    fmt,
+    sync::atomic::{AtomicU64, Ordering},
+    time::Duration,
+};
+
+use futures::future::BoxFuture;
+
+use crate::{cloned::AsyncClone, timer::Timer, Error, Work};
+
+type Predicate = Arc<dyn Fn(&Error) -> bool + Send + Sync>;
+
+pub struct RetryPolicy<T> {
+    timer: T,
+    max_attempts: usize,
+    base_delay: Duration,
+    max_delay: Duration,
+    jitter: bool,
+    predicate: Option<Predicate>,
+}
+
+impl<T: Clone> Clone for RetryPolicy<T> {
+    fn clone(&self) -> Self {
+        RetryPolicy {
+            timer: self.timer.clone(),
+            max_attempts: self.max_attempts,
+            base_delay: self.base_delay,
+            max_delay: self.max_delay,
+            jitter: self.jitter,
+            predicate: self.predicate.clone(),
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for RetryPolicy<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RetryPolicy")
+            .field("timer", &self.timer)
+            .field("max_attempts", &self.max_attempts)
+            .field("base_delay", &self.base_delay)
+            .field("max_delay", &self.max_delay)
+            .field("jitter", &self.jitter)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl Default for RetryPolicy<crate::timer::TokioTimer> {
+    fn default() -> Self {
+        RetryPolicy::new(crate::timer::TokioTimer)
+    }
+}
+
+impl<T> RetryPolicy<T> {
+    /// Three attempts, with delays doubling from 100ms up to 10s
+    pub fn new(timer: T) -> RetryPolicy<T> {
+        RetryPolicy {
+            timer,
+            max_attempts: 3,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(10),
+            jitter: true,
+            predicate: None,
+        }
+    }
+
+    /// Total number of calls, including the first one. Zero is treated as one.
+    pub fn max_attempts(mut self, attempts: usize) -> Self {
+        self.max_attempts = attempts.max(1);
+        self
+    }
+
+    /// Delay before the first retry, doubled for every following retry up to `max`
+    pub fn backoff(mut self, base: Duration, max: Duration) -> Self {
+        self.base_delay = base;
+        self.max_delay = max;
+        self
+    }
+
+    /// Randomize each delay to between half and all of its value
+    pub fn jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Only retry errors matching `predicate`. By default every error is retried.
+    pub fn retry_if<F>(mut self, predicate: F) -> Self
+    where
+        F: Fn(&Error) -> bool + Send + Sync + 'static,
+    {
+        self.predicate = Some(Arc::new(predicate));
+        self
+    }
+
+    fn should_retry(&self, error: &Error) -> bool {
+        self.predicate
+            .as_ref()
+            .is_none_or(|predicate| predicate(error))
+    }
+
+    fn delay(&self, retry: u32) -> Duration {
+        let delay = self
+            .base_delay
+            .checked_mul(2u32.saturating_pow(retry))
+            .unwrap_or(self.max_delay)
+            .min(self.max_delay);
+
+        if self.jitter {
+            let half = delay / 2;
+            half + half.mul_f64(random_unit())
+        } else {
+            delay
+        }
+    }
+}
+
+// Not cryptographic, only used to spread out retries
+fn random_unit() -> f64 {
+    static STATE: AtomicU64 = AtomicU64::new(0x2545_f491_4f6c_dd1d);
+    let mut z = STATE
+        .fetch_add(0x9e37_79b9_7f4a_7c15, Ordering::Relaxed)
+        .wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^= z >> 31;
+    (z >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[derive(Debug, Clone)]
+pub struct Retry<W, T> {
+    work: W,
+    policy: RetryPolicy<T>,
+}
+
+impl<W, T> Retry<W, T> {
+    pub fn new(work: W, policy: RetryPolicy<T>) -> Retry<W, T> {
+        Retry { work, policy }
+    }
+}
+
+impl<W, T, C, R> Work<C, R> for Retry<W, T>
+where
+    W: Work<C, R> + Sync,
+    for<'a> W::Future<'a>: Send,
+    T: Timer + Sync,
+    T::Sleep: Send,
+    R: AsyncClone + Send,
+    for<'a> R::Future<'a>: Send,
+    C: Clone + Send + 'static,
+    R: 'static,
+{
+    type Output = W::Output;
+
+    type Future<'a>
+        = BoxFuture<'a, Result<W::Output, Error>>
+    where
+        Self: 'a;
+
+    fn call<'a>(&'a self, ctx: C, mut package: R) -> Self::Future<'a> {
+        Box::pin(async move {
+            let mut retry = 0;
+            loop {
+                if retry + 1 >= self.policy.max_attempts {
+                    // Last attempt, no need to keep a copy around
+                    return self.work.call(ctx, package).await;
+                }
+
+                let clone = package.async_clone().await?;
+                let err = match self.work.call(ctx.clone(), clone).await {
+                    Ok(ret) => return Ok(ret),
+                    Err(err) => err,
+                };
+
+                if !self.policy.should_retry(&err) {
+                    return Err(err);
+                }
+
+                self.policy
+                    .timer
+                    .sleep(self.policy.delay(retry as u32))
+                    .await;
+                retry += 1;
+            }
+        })
+    }
+}
@@ -0,0 +1,117 @@
+use alloc::boxed::Box;
+use futures::future::BoxFuture;
+
+use crate::{matcher::Matcher, Error, Work};
+
+/// An ordered set of matcher/work pairs producing a common output
+pub trait Routes<C, T, O> {
+    /// Call the first work whose matcher accepts the package, or hand back the
+    /// context and package when no matcher does
+    fn dispatch<'a>(
+        &'a self,
+        ctx: C,
+        package: T,
+    ) -> Result<BoxFuture<'a, Result<O, Error>>, (C, T)>
+    where
+        C: 'a,
+        T: 'a;
+}
+
+impl<C, T, O> Routes<C, T, O> for () {
+    fn dispatch<'a>(&'a self, ctx: C, package: T) -> Result<BoxFuture<'a, Result<O, Error>>, (C, T)>
+    where
+        C: 'a,
+        T: 'a,
+    {
+        Err((ctx, package))
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Arm<M, W> {
+    matcher: M,
+    work: W,
+}
+
+impl<R, M, W, C, T, O> Routes<C, T, O> for (R, Arm<M, W>)
+where
+    R: Routes<C, T, O>,
+    M: Matcher<T>,
+    W: Work<C, T, Output = O>,
+    for<'a> W::Future<'a>: Send,
+{
+    fn dispatch<'a>(&'a self, ctx: C, package: T) -> Result<BoxFuture<'a, Result<O, Error>>, (C, T)>
+    where
+        C: 'a,
+        T: 'a,
+    {
+        // Earlier arms are nested deeper and get the first chance to match
+        let (ctx, package) = match self.0.dispatch(ctx, package) {
+            Ok(future) => return Ok(future),
+            Err(rejected) => rejected,
+        };
+
+        if self.1.matcher.is_match(&package) {
+            Ok(Box::pin(self.1.work.call(ctx, package)))
+        } else {
+            Err((ctx, package))
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Route<R, F> {
+    routes: R,
+    fallback: F,
+}
+
+impl<R, F> Route<R, F> {
+    /// Add a work for packages accepted by `matcher`.
+    /// Arms are tried in the order they were added.
+    pub fn on<M, W>(self, matcher: M, work: W) -> Route<(R, Arm<M, W>), F> {
+        Route {
+            routes: (self.routes, Arm { matcher, work }),
+            fallback: self.fallback,
+        }
+    }
+
+    /// Work for packages no arm matched
+    pub fn otherwise<W>(self, work: W) -> Route<R, W> {
+        Route {
+            routes: self.routes,
+            fallback: work,
+        }
+    }
+}
+
+impl<R, F, C, T> Work<C, T> for Route<R, F>
+where
+    R: Routes<C, T, F::Output> + Sync,
+    F: Work<C, T> + Sync,
+    for<'a> F::Future<'a>: Send,
+    C: 'static,
+    T: 'static,
+{
+    type Output = F::Output;
+
+    type Future<'a>
+        = BoxFuture<'a, Result<F::Output, Error>>
+    where
+        Self: 'a;
+
+    fn call<'a>(&'a self, ctx: C, package: T) -> Self::Future<'a> {
+        match self.routes.dispatch(ctx, package) {
+            Ok(future) => future,
+            Err((ctx, package)) => Box::pin(self.fallback.call(ctx, package)),
+        }
+    }
+}
+
+/// Dispatch packages to one of several works by the first matching matcher.
+/// The route only becomes a work once a fallback is given with [`Route::otherwise`].
+pub fn route() -> Route<(), ()> {
+    Route {
+        routes: (),
+        fallback: (),
+    }
+}
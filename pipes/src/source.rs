@@ -1,10 +1,24 @@
-use core::{mem::transmute, task::Poll};
+use core::{mem::transmute, task::Poll, time::Duration};
 
 use either::Either;
 use futures::{ready, stream::TryFlatten, Stream, TryFuture, TryStream, TryStreamExt};
 use pin_project_lite::pin_project;
 
-use crate::{and::And, cloned::AsyncCloned, error::Error, then::Then, Pipeline, SourceUnit, Work};
+use crate::{
+    and::And,
+    chunks::{Chunks, ChunksTimeout, Unbatch},
+    cloned::AsyncCloned,
+    concurrent::Concurrent,
+    distinct::{ConflictPolicy, Distinct},
+    error::Error,
+    fan_out::{Branches, FanOut},
+    fold::Fold,
+    group_by::GroupBy,
+    merge::{Chain, MergeSortedBy, Priority},
+    then::Then,
+    timer::Timer,
+    Pipeline, SourceUnit, Work,
+};
 
 pub trait Source<C> {
     type Item;
@@ -41,6 +55,36 @@ pub trait SourceExt<C>: Source<C> {
         And::new(self, source)
     }
 
+    /// Yield every item of `self`, then every item of `source`
+    fn chain<S>(self, source: S) -> Chain<Self, S>
+    where
+        Self: Sized,
+        S: Source<C, Item = Self::Item>,
+    {
+        Chain::new(self, source)
+    }
+
+    /// Interleave with `source`, always taking from `self` when both have an item ready
+    fn merge_priority<S>(self, source: S) -> Priority<Self, S>
+    where
+        Self: Sized,
+        S: Source<C, Item = Self::Item>,
+    {
+        Priority::new(self, source)
+    }
+
+    /// Merge with `source` by taking the item with the smallest key from either side.
+    /// If both sources are sorted by the key, so is the output.
+    fn merge_sorted_by<S, F, K>(self, source: S, func: F) -> MergeSortedBy<Self, S, F>
+    where
+        Self: Sized,
+        S: Source<C, Item = Self::Item>,
+        F: Fn(&Self::Item) -> K,
+        K: Ord,
+    {
+        MergeSortedBy::new(self, source, func)
+    }
+
     fn filter<W>(self, work: W) -> Filter<Self, W>
     where
         Self: Sized,
@@ -57,6 +101,14 @@ pub trait SourceExt<C>: Source<C> {
         Pipeline::new_with(self, work)
     }
 
+    fn pipe_concurrent<W>(self, work: W, limit: usize) -> Concurrent<Self, W, C>
+    where
+        Self: Sized,
+        W: Work<C, Self::Item>,
+    {
+        Concurrent::new(self, work, limit)
+    }
+
     fn flatten(self) -> Flatten<Self>
     where
         Self: Sized,
@@ -65,6 +117,77 @@ pub trait SourceExt<C>: Source<C> {
         Flatten { source: self }
     }
 
+    fn chunks(self, size: usize) -> Chunks<Self>
+    where
+        Self: Sized,
+    {
+        Chunks::new(self, size)
+    }
+
+    #[cfg(feature = "tokio")]
+    fn chunks_timeout(
+        self,
+        size: usize,
+        duration: Duration,
+    ) -> ChunksTimeout<Self, crate::timer::TokioTimer>
+    where
+        Self: Sized,
+    {
+        ChunksTimeout::new(self, size, duration, crate::timer::TokioTimer)
+    }
+
+    fn chunks_timeout_with<T>(
+        self,
+        size: usize,
+        duration: Duration,
+        timer: T,
+    ) -> ChunksTimeout<Self, T>
+    where
+        Self: Sized,
+        T: Timer,
+    {
+        ChunksTimeout::new(self, size, duration, timer)
+    }
+
+    /// Consume the whole source into `init`, yielding the accumulated value once the source ends
+    fn fold<A, F, U>(self, init: A, func: F) -> Fold<Self, A, F>
+    where
+        Self: Sized,
+        F: Fn(C, A, Self::Item) -> U,
+        U: TryFuture<Ok = A>,
+        U::Error: Into<Error>,
+    {
+        Fold::new(self, init, func)
+    }
+
+    /// Collect items into groups by key, yielding each group in key order once the source ends
+    fn group_by<F, K>(self, func: F) -> GroupBy<Self, F>
+    where
+        Self: Sized,
+        F: Fn(&Self::Item) -> K,
+        K: Ord,
+    {
+        GroupBy::new(self, func)
+    }
+
+    /// Drop or reject items whose key has already been seen
+    fn distinct_by<F, K>(self, func: F, policy: ConflictPolicy) -> Distinct<Self, F>
+    where
+        Self: Sized,
+        F: Fn(&Self::Item) -> K,
+        K: Ord + core::fmt::Debug,
+    {
+        Distinct::new(self, func, policy)
+    }
+
+    fn unbatch(self) -> Unbatch<Self>
+    where
+        Self: Sized,
+        Self::Item: IntoIterator,
+    {
+        Unbatch::new(self)
+    }
+
     fn cloned<T1, T2>(self, work1: T1, work2: T2) -> AsyncCloned<Self, T1, T2>
     where
         Self: Sized,
@@ -72,6 +195,14 @@ pub trait SourceExt<C>: Source<C> {
         AsyncCloned::new(self, work1, work2)
     }
 
+    fn fan_out<B>(self, branches: B) -> FanOut<Self, B>
+    where
+        Self: Sized,
+        B: Branches<C, Self::Item>,
+    {
+        FanOut::new(self, branches)
+    }
+
     fn then<W>(self, work: W) -> Then<Self, W>
     where
         Self: Sized,
@@ -80,6 +211,15 @@ pub trait SourceExt<C>: Source<C> {
         Then::new(self, work)
     }
 
+    #[cfg(feature = "tokio")]
+    fn spawn<W>(self, work: W) -> crate::spawn::Spawn<Self, W>
+    where
+        Self: Sized,
+        W: Work<C, Self::Item>,
+    {
+        crate::spawn::Spawn::new(self, work)
+    }
+
     fn unit(self) -> SourceUnit<Self>
     where
         Self: Sized,
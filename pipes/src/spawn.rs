@@ -0,0 +1,114 @@
+use core::{future::Future, pin::Pin, task::Poll};
+
+use futures::{ready, Stream, StreamExt};
+use tokio::{sync::mpsc, task::JoinHandle};
+
+use crate::{error::Error, source::Source, work::Work};
+
+const DEFAULT_BUFFER: usize = 10;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Spawn<S, W> {
+    source: S,
+    work: W,
+    buffer: usize,
+}
+
+impl<S, W> Spawn<S, W> {
+    pub fn new(source: S, work: W) -> Spawn<S, W> {
+        Spawn {
+            source,
+            work,
+            buffer: DEFAULT_BUFFER,
+        }
+    }
+
+    /// Number of finished items the task may get ahead of the consumer. A size of zero is treated as one.
+    pub fn buffer(mut self, size: usize) -> Self {
+        self.buffer = size.max(1);
+        self
+    }
+}
+
+impl<S, W, C> Source<C> for Spawn<S, W>
+where
+    S: Source<C> + Send + 'static,
+    S::Item: Send,
+    for<'a> S::Stream<'a>: Send,
+    W: Work<C, S::Item> + Send + Sync + 'static,
+    W::Output: Send + 'static,
+    for<'a> W::Future<'a>: Send,
+    C: Clone + Send + 'static,
+{
+    type Item = W::Output;
+
+    type Stream<'a>
+        = SpawnStream<W::Output>
+    where
+        Self: 'a;
+
+    fn create_stream<'a>(self, ctx: C) -> Self::Stream<'a> {
+        let (sx, rx) = mpsc::channel(self.buffer);
+
+        let handle = tokio::spawn(async move {
+            let stream = self.source.create_stream(ctx.clone());
+            futures::pin_mut!(stream);
+            while let Some(item) = stream.next().await {
+                let ret = match item {
+                    Ok(ret) => self.work.call(ctx.clone(), ret).await,
+                    Err(err) => Err(err),
+                };
+
+                if sx.send(ret).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        SpawnStream {
+            rx,
+            handle: Some(handle),
+        }
+    }
+}
+
+/// Receiving end of a [`Spawn`] task. Dropping the stream aborts the task.
+#[derive(Debug)]
+pub struct SpawnStream<T> {
+    rx: mpsc::Receiver<Result<T, Error>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl<T> Stream for SpawnStream<T> {
+    type Item = Result<T, Error>;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut core::task::Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        if let Some(item) = ready!(self.rx.poll_recv(cx)) {
+            return Poll::Ready(Some(item));
+        }
+
+        // The channel is closed: surface a panic in the task, if any
+        let Some(handle) = self.handle.as_mut() else {
+            return Poll::Ready(None);
+        };
+
+        let ret = ready!(Pin::new(handle).poll(cx));
+        self.handle = None;
+
+        match ret {
+            Ok(()) => Poll::Ready(None),
+            Err(err) => Poll::Ready(Some(Err(Error::new(err)))),
+        }
+    }
+}
+
+impl<T> Drop for SpawnStream<T> {
+    fn drop(&mut self) {
+        if let Some(handle) = self.handle.take() {
+            handle.abort();
+        }
+    }
+}
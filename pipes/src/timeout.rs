@@ -0,0 +1,99 @@
+use core::{fmt, task::Poll, time::Duration};
+
+use futures::Future;
+use pin_project_lite::pin_project;
+
+use crate::{timer::Timer, Error, Work};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeoutError {
+    duration: Duration,
+}
+
+impl TimeoutError {
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+}
+
+impl fmt::Display for TimeoutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "timed out after {:?}", self.duration)
+    }
+}
+
+impl core::error::Error for TimeoutError {}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Timeout<W, T> {
+    work: W,
+    duration: Duration,
+    timer: T,
+}
+
+impl<W, T> Timeout<W, T> {
+    pub fn new(work: W, duration: Duration, timer: T) -> Timeout<W, T> {
+        Timeout {
+            work,
+            duration,
+            timer,
+        }
+    }
+}
+
+impl<W, T, C, R> Work<C, R> for Timeout<W, T>
+where
+    W: Work<C, R>,
+    T: Timer,
+{
+    type Output = W::Output;
+
+    type Future<'a>
+        = TimeoutFuture<W::Future<'a>, T::Sleep>
+    where
+        Self: 'a;
+
+    fn call<'a>(&'a self, ctx: C, package: R) -> Self::Future<'a> {
+        TimeoutFuture {
+            future: self.work.call(ctx, package),
+            sleep: self.timer.sleep(self.duration),
+            duration: self.duration,
+        }
+    }
+}
+
+pin_project! {
+    pub struct TimeoutFuture<F, S> {
+        #[pin]
+        future: F,
+        #[pin]
+        sleep: S,
+        duration: Duration,
+    }
+}
+
+impl<F, S, O> Future for TimeoutFuture<F, S>
+where
+    F: Future<Output = Result<O, Error>>,
+    S: Future<Output = ()>,
+{
+    type Output = Result<O, Error>;
+
+    fn poll(
+        self: core::pin::Pin<&mut Self>,
+        cx: &mut core::task::Context<'_>,
+    ) -> Poll<Self::Output> {
+        let this = self.project();
+
+        if let Poll::Ready(ret) = this.future.poll(cx) {
+            return Poll::Ready(ret);
+        }
+
+        match this.sleep.poll(cx) {
+            Poll::Ready(()) => Poll::Ready(Err(Error::new(TimeoutError {
+                duration: *this.duration,
+            }))),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
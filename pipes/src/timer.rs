@@ -0,0 +1,35 @@
+use core::time::Duration;
+
+use futures::Future;
+
+/// Runtime agnostic source of delays
+pub trait Timer {
+    type Sleep: Future<Output = ()>;
+
+    fn sleep(&self, duration: Duration) -> Self::Sleep;
+}
+
+impl<F, U> Timer for F
+where
+    F: Fn(Duration) -> U,
+    U: Future<Output = ()>,
+{
+    type Sleep = U;
+
+    fn sleep(&self, duration: Duration) -> Self::Sleep {
+        (self)(duration)
+    }
+}
+
+#[cfg(feature = "tokio")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TokioTimer;
+
+#[cfg(feature = "tokio")]
+impl Timer for TokioTimer {
+    type Sleep = tokio::time::Sleep;
+
+    fn sleep(&self, duration: Duration) -> Self::Sleep {
+        tokio::time::sleep(duration)
+    }
+}
@@ -1,12 +1,14 @@
+use alloc::vec::Vec;
 use core::task::Poll;
 
 use futures::{ready, Future, TryStream};
 use pin_project_lite::pin_project;
 
-use crate::{and::And, Source};
+use crate::{and::And, Error, Source};
 
 pub trait Unit<C> {
-    type Future<'a>: Future<Output = ()>
+    type Output;
+    type Future<'a>: Future<Output = Self::Output>
     where
         Self: 'a;
     fn run<'a>(self, ctx: C) -> Self::Future<'a>;
@@ -33,6 +35,14 @@ impl<S> SourceUnit<S> {
     pub fn new(source: S) -> SourceUnit<S> {
         SourceUnit { source }
     }
+
+    /// Run the source while recording failures instead of discarding them
+    pub fn report(self, policy: ErrorPolicy) -> ReportUnit<S> {
+        ReportUnit {
+            source: self.source,
+            policy,
+        }
+    }
 }
 
 impl<S, C> Unit<C> for SourceUnit<S>
@@ -40,6 +50,7 @@ where
     S: Source<C> + 'static,
     for<'a> S::Item: 'a,
 {
+    type Output = ();
     type Future<'a> = SourceUnitFure<'a, S, C>;
 
     fn run<'a>(self, ctx: C) -> Self::Future<'a> {
@@ -78,3 +89,129 @@ where
         })
     }
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ErrorPolicy {
+    /// Stop at the first failing item
+    FailFast,
+    /// Record the failure and keep going
+    #[default]
+    Continue,
+}
+
+#[derive(Debug)]
+pub struct Failure {
+    index: usize,
+    error: Error,
+}
+
+impl Failure {
+    /// Position of the failing item in the source
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Path of the failing package, if known
+    pub fn path(&self) -> Option<&str> {
+        self.error.path()
+    }
+
+    pub fn error(&self) -> &Error {
+        &self.error
+    }
+
+    pub fn into_error(self) -> Error {
+        self.error
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Report {
+    processed: usize,
+    failures: Vec<Failure>,
+}
+
+impl Report {
+    /// Number of items processed, including failed ones
+    pub fn processed(&self) -> usize {
+        self.processed
+    }
+
+    pub fn failures(&self) -> &[Failure] {
+        &self.failures
+    }
+
+    pub fn into_failures(self) -> Vec<Failure> {
+        self.failures
+    }
+
+    pub fn is_success(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ReportUnit<S> {
+    source: S,
+    policy: ErrorPolicy,
+}
+
+impl<S, C> Unit<C> for ReportUnit<S>
+where
+    S: Source<C> + 'static,
+    for<'a> S::Item: 'a,
+{
+    type Output = Report;
+    type Future<'a> = ReportFuture<'a, S, C>;
+
+    fn run<'a>(self, ctx: C) -> Self::Future<'a> {
+        ReportFuture {
+            stream: self.source.create_stream(ctx),
+            policy: self.policy,
+            report: Report::default(),
+        }
+    }
+}
+
+pin_project! {
+    #[project(!Unpin)]
+    pub struct ReportFuture<'a, S, C> where S: Source<C>, S: 'a, S::Item: 'a {
+        #[pin]
+        stream: S::Stream<'a>,
+        policy: ErrorPolicy,
+        report: Report,
+    }
+}
+
+impl<'a, S, C> Future for ReportFuture<'a, S, C>
+where
+    S: Source<C> + 'a,
+{
+    type Output = Report;
+
+    fn poll(
+        mut self: core::pin::Pin<&mut Self>,
+        cx: &mut core::task::Context<'_>,
+    ) -> Poll<Self::Output> {
+        let mut this = self.as_mut().project();
+
+        loop {
+            match ready!(this.stream.as_mut().try_poll_next(cx)) {
+                Some(Ok(_)) => {
+                    this.report.processed += 1;
+                }
+                Some(Err(error)) => {
+                    let index = this.report.processed;
+                    this.report.processed += 1;
+                    this.report.failures.push(Failure { index, error });
+                    if *this.policy == ErrorPolicy::FailFast {
+                        break;
+                    }
+                }
+                None => break,
+            }
+        }
+
+        Poll::Ready(core::mem::take(this.report))
+    }
+}
@@ -1,13 +1,19 @@
-use core::task::Poll;
+use alloc::borrow::Cow;
+use core::{task::Poll, time::Duration};
 use either::Either;
 use futures::{ready, Future, TryFuture};
 use pin_project_lite::pin_project;
 
 use crate::{
     and::And,
+    cloned::AsyncClone,
     error::Error,
+    named::Named,
+    retry::{Retry, RetryPolicy},
     split::{Anyways, Split},
     then::Then,
+    timeout::Timeout,
+    timer::Timer,
     wrap::Wrap,
 };
 
@@ -57,6 +63,38 @@ pub trait WorkExt<C, T>: Work<C, T> {
     {
         Split::new(self, left, right)
     }
+
+    fn named(self, name: impl Into<Cow<'static, str>>) -> Named<Self>
+    where
+        Self: Sized,
+    {
+        Named::new(self, name)
+    }
+
+    fn retry<P>(self, policy: RetryPolicy<P>) -> Retry<Self, P>
+    where
+        Self: Sized,
+        P: Timer,
+        T: AsyncClone,
+    {
+        Retry::new(self, policy)
+    }
+
+    #[cfg(feature = "tokio")]
+    fn timeout(self, duration: Duration) -> Timeout<Self, crate::timer::TokioTimer>
+    where
+        Self: Sized,
+    {
+        Timeout::new(self, duration, crate::timer::TokioTimer)
+    }
+
+    fn timeout_with<P>(self, duration: Duration, timer: P) -> Timeout<Self, P>
+    where
+        Self: Sized,
+        P: Timer,
+    {
+        Timeout::new(self, duration, timer)
+    }
 }
 
 impl<T, R, C> WorkExt<C, R> for T where T: Work<C, R> {}
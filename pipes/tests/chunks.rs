@@ -0,0 +1,83 @@
+#![cfg(feature = "tokio")]
+
+use std::time::Duration;
+
+use futures::{stream::BoxStream, StreamExt};
+use pipes::{Error, Source, SourceExt};
+use tokio::time::Instant;
+
+/// Yields each value after sleeping that many milliseconds
+struct Delayed(Vec<u64>);
+
+impl Source<()> for Delayed {
+    type Item = u64;
+
+    type Stream<'a> = BoxStream<'a, Result<u64, Error>>;
+
+    fn create_stream<'a>(self, _ctx: ()) -> Self::Stream<'a> {
+        async_stream::stream! {
+            for ms in self.0 {
+                tokio::time::sleep(Duration::from_millis(ms)).await;
+                yield Ok(ms);
+            }
+        }
+        .boxed()
+    }
+}
+
+async fn batches<S: Source<(), Item = Vec<u64>>>(source: S) -> Vec<Vec<u64>> {
+    source
+        .create_stream(())
+        .map(|ret| ret.unwrap())
+        .collect()
+        .await
+}
+
+#[tokio::test(start_paused = true)]
+async fn partial_batch_is_flushed_when_the_timer_fires() {
+    let source = Delayed(vec![1, 10, 200]).chunks_timeout(3, Duration::from_millis(100));
+
+    let stream = source.create_stream(());
+    futures::pin_mut!(stream);
+    let start = Instant::now();
+
+    let first = stream.next().await.unwrap().unwrap();
+    assert_eq!(first, vec![1, 10]);
+    // Measured from the first item of the batch
+    assert_eq!(start.elapsed(), Duration::from_millis(101));
+
+    assert_eq!(stream.next().await.unwrap().unwrap(), vec![200]);
+    assert!(stream.next().await.is_none());
+}
+
+#[tokio::test(start_paused = true)]
+async fn timer_restarts_after_a_full_batch() {
+    // Without a restart the timer of the first batch would fire at 100ms and split the second
+    let source = Delayed(vec![0, 10, 50, 80]).chunks_timeout(2, Duration::from_millis(100));
+
+    assert_eq!(batches(source).await, vec![vec![0, 10], vec![50, 80]]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn tail_is_flushed_at_the_end() {
+    let source = Delayed(vec![0, 0, 0, 0, 0]).chunks_timeout(2, Duration::from_secs(60));
+
+    let start = Instant::now();
+    assert_eq!(batches(source).await, vec![vec![0, 0], vec![0, 0], vec![0]]);
+    // The tail does not wait for the timer
+    assert_eq!(start.elapsed(), Duration::ZERO);
+}
+
+#[tokio::test]
+async fn unbatch_undoes_chunks() {
+    let items = (0..7).map(Ok).collect::<Vec<Result<u32, Error>>>();
+
+    let batches = SourceExt::<()>::chunks(items, 3);
+    let output = SourceExt::<()>::unbatch(batches)
+        .create_stream(())
+        .map(|ret| ret.unwrap())
+        .collect::<Vec<_>>()
+        .await;
+
+    assert_eq!(output, (0..7).collect::<Vec<_>>());
+}
@@ -0,0 +1,92 @@
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use futures::StreamExt;
+use pipes::{work_fn, Error, Source, SourceExt};
+
+async fn delayed(_ctx: (), ms: u64) -> Result<u64, Error> {
+    tokio::time::sleep(Duration::from_millis(ms)).await;
+    Ok(ms)
+}
+
+#[tokio::test]
+async fn ordered_yields_in_source_order() {
+    let source = vec![Ok(30), Ok(10), Ok(20)];
+
+    let output = source
+        .pipe_concurrent(work_fn(delayed), 3)
+        .create_stream(())
+        .map(|ret| ret.unwrap())
+        .collect::<Vec<_>>()
+        .await;
+
+    assert_eq!(output, vec![30, 10, 20]);
+}
+
+#[tokio::test]
+async fn unordered_yields_on_completion() {
+    let source = vec![Ok(30), Ok(10), Ok(20)];
+
+    let output = source
+        .pipe_concurrent(work_fn(delayed), 3)
+        .unordered()
+        .create_stream(())
+        .map(|ret| ret.unwrap())
+        .collect::<Vec<_>>()
+        .await;
+
+    assert_eq!(output, vec![10, 20, 30]);
+}
+
+#[tokio::test]
+async fn ordered_source_errors_keep_their_place() {
+    let source = vec![Ok(30), Err(Error::new("source")), Ok(10)];
+
+    let output = source
+        .pipe_concurrent(work_fn(delayed), 3)
+        .create_stream(())
+        .map(|ret| ret.ok())
+        .collect::<Vec<_>>()
+        .await;
+
+    assert_eq!(output, vec![Some(30), None, Some(10)]);
+}
+
+#[tokio::test]
+async fn limits_in_flight_work() {
+    let running = Arc::new(AtomicUsize::new(0));
+    let max = Arc::new(AtomicUsize::new(0));
+
+    let work = {
+        let running = running.clone();
+        let max = max.clone();
+        work_fn(move |_ctx: (), item: u64| {
+            let running = running.clone();
+            let max = max.clone();
+            async move {
+                let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                max.fetch_max(now, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(10)).await;
+                running.fetch_sub(1, Ordering::SeqCst);
+                Result::<_, Error>::Ok(item)
+            }
+        })
+    };
+
+    let output = (0..10)
+        .map(Ok)
+        .collect::<Vec<_>>()
+        .pipe_concurrent(work, 3)
+        .create_stream(())
+        .map(|ret| ret.unwrap())
+        .collect::<Vec<_>>()
+        .await;
+
+    assert_eq!(output, (0..10).collect::<Vec<_>>());
+    assert_eq!(max.load(Ordering::SeqCst), 3);
+}
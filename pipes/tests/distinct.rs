@@ -0,0 +1,53 @@
+use futures::StreamExt;
+use pipes::{ConflictPolicy, DuplicateKey, Error, Source, SourceExt};
+
+type Item = (&'static str, u32);
+
+fn items(items: Vec<Result<Item, Error>>, policy: ConflictPolicy) -> impl Source<(), Item = Item> {
+    SourceExt::<()>::distinct_by(items, |item: &Item| item.0, policy)
+}
+
+#[tokio::test]
+async fn last_wins_replaces_in_place_and_holds_items_back() {
+    let source = items(
+        vec![
+            Ok(("a", 1)),
+            Ok(("b", 2)),
+            Err(Error::new("source")),
+            Ok(("a", 3)),
+        ],
+        ConflictPolicy::LastWins,
+    );
+
+    let output = source
+        .create_stream(())
+        .map(|ret| ret.map_err(|err| err.to_string()))
+        .collect::<Vec<_>>()
+        .await;
+
+    // Errors pass straight through, items only come once the source has ended
+    assert_eq!(
+        output,
+        vec![Err("source".to_string()), Ok(("a", 3)), Ok(("b", 2))]
+    );
+}
+
+#[tokio::test]
+async fn error_policy_yields_duplicate_keys() {
+    let source = items(
+        vec![Ok(("a", 1)), Ok(("b", 2)), Ok(("a", 3))],
+        ConflictPolicy::Error,
+    );
+
+    let output = source.create_stream(()).collect::<Vec<_>>().await;
+
+    assert_eq!(output.len(), 3);
+    assert_eq!(output[0].as_ref().unwrap(), &("a", 1));
+    assert_eq!(output[1].as_ref().unwrap(), &("b", 2));
+
+    let err = output[2].as_ref().unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<DuplicateKey>().map(DuplicateKey::key),
+        Some("\"a\"")
+    );
+}
@@ -0,0 +1,24 @@
+use futures::StreamExt;
+use pipes::{Error, Source, SourceExt};
+
+#[tokio::test]
+async fn merge_sorted_by_reports_errors_from_both_sides() {
+    let left: Vec<Result<u32, Error>> = vec![Err(Error::new("left")), Ok(1)];
+    let right: Vec<Result<u32, Error>> = vec![Err(Error::new("right")), Ok(2)];
+
+    let output = SourceExt::<()>::merge_sorted_by(left, right, |item: &u32| *item)
+        .create_stream(())
+        .map(|ret| ret.map_err(|err| err.to_string()))
+        .collect::<Vec<_>>()
+        .await;
+
+    assert_eq!(
+        output,
+        vec![
+            Err("left".to_string()),
+            Err("right".to_string()),
+            Ok(1),
+            Ok(2)
+        ]
+    );
+}
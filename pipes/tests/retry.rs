@@ -0,0 +1,125 @@
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use futures::future::{ready, Ready};
+use pipes::{AsyncClone, Error, RetryPolicy, Work, WorkExt};
+
+/// Counts how often it is cloned
+struct Input {
+    value: u32,
+    clones: Arc<AtomicUsize>,
+}
+
+impl AsyncClone for Input {
+    type Future<'a> = Ready<Result<Self, Error>>;
+
+    fn async_clone<'a>(&'a mut self) -> Self::Future<'a> {
+        self.clones.fetch_add(1, Ordering::SeqCst);
+        ready(Ok(Input {
+            value: self.value,
+            clones: self.clones.clone(),
+        }))
+    }
+}
+
+/// Fails the first `failures` calls, then yields the input value
+struct Flaky {
+    calls: Arc<AtomicUsize>,
+    failures: usize,
+    error: &'static str,
+}
+
+impl Flaky {
+    fn new(calls: &Arc<AtomicUsize>, failures: usize) -> Flaky {
+        Flaky {
+            calls: calls.clone(),
+            failures,
+            error: "flaky",
+        }
+    }
+}
+
+impl Work<(), Input> for Flaky {
+    type Output = u32;
+
+    type Future<'a> = Ready<Result<u32, Error>>;
+
+    fn call<'a>(&'a self, _ctx: (), input: Input) -> Self::Future<'a> {
+        if self.calls.fetch_add(1, Ordering::SeqCst) < self.failures {
+            ready(Err(Error::new(self.error)))
+        } else {
+            ready(Ok(input.value))
+        }
+    }
+}
+
+fn policy() -> RetryPolicy<impl Fn(Duration) -> Ready<()> + Clone> {
+    RetryPolicy::new(|_: Duration| ready(()))
+}
+
+fn input(clones: &Arc<AtomicUsize>) -> Input {
+    Input {
+        value: 7,
+        clones: clones.clone(),
+    }
+}
+
+#[tokio::test]
+async fn max_attempts_counts_every_call() {
+    let clones = Arc::new(AtomicUsize::new(0));
+
+    for (attempts, calls_made) in [(3, 3), (1, 1), (0, 1)] {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let work = Flaky::new(&calls, usize::MAX).retry(policy().max_attempts(attempts));
+
+        let ret = work.call((), input(&clones)).await;
+        assert_eq!(ret.unwrap_err().to_string(), "flaky");
+        assert_eq!(calls.load(Ordering::SeqCst), calls_made);
+    }
+}
+
+#[tokio::test]
+async fn input_is_replayed_for_every_attempt() {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let clones = Arc::new(AtomicUsize::new(0));
+    let work = Flaky::new(&calls, 2).retry(policy().max_attempts(3));
+
+    assert_eq!(work.call((), input(&clones)).await.unwrap(), 7);
+    assert_eq!(calls.load(Ordering::SeqCst), 3);
+    // The last attempt gets the original
+    assert_eq!(clones.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn success_ends_the_retries() {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let clones = Arc::new(AtomicUsize::new(0));
+    let work = Flaky::new(&calls, 1).retry(policy().max_attempts(5));
+
+    assert_eq!(work.call((), input(&clones)).await.unwrap(), 7);
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn retry_if_stops_on_other_errors() {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let clones = Arc::new(AtomicUsize::new(0));
+    let work = Flaky {
+        error: "fatal",
+        ..Flaky::new(&calls, usize::MAX)
+    }
+    .retry(
+        policy()
+            .max_attempts(5)
+            .retry_if(|err| err.to_string() != "fatal"),
+    );
+
+    let ret = work.call((), input(&clones)).await;
+    assert_eq!(ret.unwrap_err().to_string(), "fatal");
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+}
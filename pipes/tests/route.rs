@@ -0,0 +1,39 @@
+use futures::future::{ready, Ready};
+use pipes::{route, Error, Work};
+
+/// Prefixes the item with its name
+#[derive(Clone, Copy)]
+struct Label(&'static str);
+
+impl Work<(), u32> for Label {
+    type Output = String;
+
+    type Future<'a> = Ready<Result<String, Error>>;
+
+    fn call<'a>(&'a self, _ctx: (), n: u32) -> Self::Future<'a> {
+        ready(Ok(format!("{} {n}", self.0)))
+    }
+}
+
+#[tokio::test]
+async fn arms_match_in_insertion_order() {
+    let work = route()
+        .on(|n: &u32| *n >= 10, Label("big"))
+        .on(|n: &u32| *n >= 5, Label("medium"))
+        .otherwise(Label("other"));
+
+    // 12 matches both arms, the first one added wins
+    assert_eq!(work.call((), 12).await.unwrap(), "big 12");
+    assert_eq!(work.call((), 7).await.unwrap(), "medium 7");
+    assert_eq!(work.call((), 1).await.unwrap(), "other 1");
+}
+
+#[tokio::test]
+async fn fallback_gets_unmatched_items() {
+    let work = route()
+        .on(|n: &u32| *n > 10, Label("big"))
+        .otherwise(Label("other"));
+
+    assert_eq!(work.call((), 11).await.unwrap(), "big 11");
+    assert_eq!(work.call((), 5).await.unwrap(), "other 5");
+}
@@ -0,0 +1,95 @@
+#![cfg(feature = "tokio")]
+
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use futures::StreamExt;
+use pipes::{work_fn, Error, Source, SourceExt};
+use tokio::{sync::Notify, task::JoinError};
+
+/// Sets its flag when dropped
+struct Guard(Arc<AtomicBool>);
+
+impl Drop for Guard {
+    fn drop(&mut self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+}
+
+#[tokio::test]
+async fn dropping_the_stream_aborts_the_task() {
+    let dropped = Arc::new(AtomicBool::new(false));
+    let started = Arc::new(Notify::new());
+
+    let work = {
+        let dropped = dropped.clone();
+        let started = started.clone();
+        work_fn(move |_ctx: (), _item: u32| {
+            let guard = Guard(dropped.clone());
+            let started = started.clone();
+            async move {
+                let _guard = guard;
+                started.notify_one();
+                futures::future::pending::<()>().await;
+                Result::<u32, Error>::Ok(0)
+            }
+        })
+    };
+
+    let stream = vec![Ok(1u32)].spawn(work).create_stream(());
+    started.notified().await;
+    assert!(!dropped.load(Ordering::SeqCst));
+
+    drop(stream);
+
+    tokio::time::timeout(Duration::from_secs(1), async {
+        while !dropped.load(Ordering::SeqCst) {
+            tokio::task::yield_now().await;
+        }
+    })
+    .await
+    .expect("task was not aborted");
+}
+
+#[tokio::test]
+async fn panics_are_yielded_as_errors() {
+    let work = work_fn(|_ctx: (), item: u32| async move {
+        if item == 2 {
+            panic!("boom");
+        }
+        Result::<_, Error>::Ok(item)
+    });
+
+    let output = vec![Ok(1), Ok(2), Ok(3)]
+        .spawn(work)
+        .create_stream(())
+        .collect::<Vec<_>>()
+        .await;
+
+    assert_eq!(output.len(), 2);
+    assert_eq!(output[0].as_ref().unwrap(), &1);
+
+    let err = output[1].as_ref().unwrap_err();
+    assert!(err
+        .downcast_ref::<JoinError>()
+        .is_some_and(JoinError::is_panic));
+}
+
+#[tokio::test]
+async fn source_errors_are_passed_on() {
+    let work = work_fn(|_ctx: (), item: u32| async move { Result::<_, Error>::Ok(item * 10) });
+
+    let output = vec![Ok(1), Err(Error::new("source")), Ok(3)]
+        .spawn(work)
+        .create_stream(())
+        .map(|ret| ret.map_err(|err| err.to_string()))
+        .collect::<Vec<_>>()
+        .await;
+
+    assert_eq!(output, vec![Ok(10), Err("source".to_string()), Ok(30)]);
+}